@@ -0,0 +1,144 @@
+use anyhow::Result;
+use async_std::fs;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::env;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    default_ttl: Duration,
+    ttls: HashMap<String, Duration>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CacheMeta {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched_at: u64,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CacheEntry {
+    pub meta: CacheMeta,
+    pub body: Vec<u8>,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or_default()
+}
+
+impl CacheEntry {
+    pub fn is_fresh(&self, ttl: Duration) -> bool {
+        now().saturating_sub(self.meta.fetched_at) < ttl.as_secs()
+    }
+
+    pub fn revalidate(&self, request: &mut surf::Request) {
+        if let Some(etag) = &self.meta.etag {
+            request.insert_header("If-None-Match", etag.as_str());
+        }
+        if let Some(last_modified) = &self.meta.last_modified {
+            request.insert_header("If-Modified-Since", last_modified.as_str());
+        }
+    }
+}
+
+impl ResponseCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            default_ttl: Duration::from_secs(300),
+            ttls: HashMap::new(),
+        }
+    }
+
+    pub fn with_default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = ttl;
+        self
+    }
+
+    pub fn with_ttl(mut self, name: impl Into<String>, ttl: Duration) -> Self {
+        self.ttls.insert(name.into(), ttl);
+        self
+    }
+
+    pub fn from_env() -> Option<Self> {
+        Self::from_vars(env::vars())
+    }
+
+    pub fn from_vars(vars: impl IntoIterator<Item = (String, String)>) -> Option<Self> {
+        let vars = vars.into_iter().collect::<HashMap<_, _>>();
+        let mut cache = Self::new(vars.get("RESPONSE_CACHE")?);
+        for (key, value) in &vars {
+            let secs = match value.parse() {
+                Ok(x) => Duration::from_secs(x),
+                Err(_) => continue,
+            };
+            if key == "RESPONSE_CACHE_TTL" {
+                cache.default_ttl = secs;
+            } else if let Some(name) = key.strip_prefix("RESPONSE_CACHE_TTL_") {
+                cache.ttls.insert(name.to_lowercase(), secs);
+            }
+        }
+        Some(cache)
+    }
+
+    pub fn ttl(&self, name: &str) -> Duration {
+        self.ttls.get(name).copied().unwrap_or(self.default_ttl)
+    }
+
+    fn paths(&self, url: &str) -> (PathBuf, PathBuf) {
+        let key = format!("{:x}", Sha256::digest(url.as_bytes()));
+        (
+            self.dir.join(format!("{}.json", key)),
+            self.dir.join(format!("{}.body", key)),
+        )
+    }
+
+    pub(crate) async fn get(&self, url: &str) -> Option<CacheEntry> {
+        let (meta_path, body_path) = self.paths(url);
+        let meta: CacheMeta = serde_json::from_slice(&fs::read(meta_path).await.ok()?).ok()?;
+        if meta.url != url {
+            return None;
+        }
+        let body = fs::read(body_path).await.ok()?;
+        Some(CacheEntry { meta, body })
+    }
+
+    pub(crate) async fn store(
+        &self,
+        url: &str,
+        etag: Option<String>,
+        last_modified: Option<String>,
+        body: &[u8],
+    ) -> Result<()> {
+        let (meta_path, body_path) = self.paths(url);
+        let meta = CacheMeta {
+            url: url.to_string(),
+            etag,
+            last_modified,
+            fetched_at: now(),
+        };
+        fs::create_dir_all(&self.dir).await?;
+        fs::write(body_path, body).await?;
+        fs::write(meta_path, serde_json::to_vec(&meta)?).await?;
+        Ok(())
+    }
+
+    pub(crate) async fn touch(&self, entry: &CacheEntry) -> Result<()> {
+        let (meta_path, _) = self.paths(&entry.meta.url);
+        let meta = CacheMeta {
+            fetched_at: now(),
+            ..entry.meta.clone()
+        };
+        fs::write(meta_path, serde_json::to_vec(&meta)?).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,269 @@
+use super::data_sources::DataSources;
+use super::decode::decode;
+use super::fetcher::Fetcher;
+use super::models::{GameUpdate, Position};
+use super::ResponseCache;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use log::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::time::Duration;
+use uuid::Uuid;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntityType {
+    Player,
+    Team,
+    Sim,
+    Stadium,
+    Idols,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Version<T> {
+    pub entity_id: Uuid,
+    #[serde(default)]
+    pub hash: String,
+    pub valid_from: DateTime<Utc>,
+    pub valid_to: Option<DateTime<Utc>>,
+    pub data: T,
+}
+
+impl<T> Version<T> {
+    pub fn map<U>(self, func: impl FnOnce(T) -> U) -> Version<U> {
+        Version {
+            entity_id: self.entity_id,
+            hash: self.hash,
+            valid_from: self.valid_from,
+            valid_to: self.valid_to,
+            data: func(self.data),
+        }
+    }
+
+    pub fn try_map<U, E>(self, func: impl FnOnce(T) -> Result<U, E>) -> Result<Version<U>, E> {
+        Ok(Version {
+            entity_id: self.entity_id,
+            hash: self.hash,
+            valid_from: self.valid_from,
+            valid_to: self.valid_to,
+            data: func(self.data)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EntityQuery {
+    pub ids: Vec<Uuid>,
+    pub at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VersionQuery {
+    pub ids: Vec<Uuid>,
+    pub before: Option<DateTime<Utc>>,
+    pub after: Option<DateTime<Utc>>,
+    pub order: Option<Order>,
+    pub count: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Page<T> {
+    #[serde(default)]
+    next_page: Option<String>,
+    #[serde(alias = "data")]
+    items: Vec<T>,
+}
+
+#[derive(Serialize)]
+struct EntitiesParams<'a> {
+    #[serde(rename = "type")]
+    kind: EntityType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct VersionsParams<'a> {
+    #[serde(rename = "type")]
+    kind: EntityType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    before: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    after: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order: Option<Order>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct PlayersParams<'a> {
+    forbidden: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct GamesParams<'a> {
+    season: isize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page: Option<&'a str>,
+}
+
+fn join_ids(ids: &[Uuid]) -> Option<String> {
+    if ids.is_empty() {
+        None
+    } else {
+        Some(
+            ids.iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Chronicler {
+    fetcher: Fetcher,
+    sources: DataSources,
+}
+
+impl Chronicler {
+    pub fn new(sources: DataSources) -> Self {
+        Self::with_fetcher(Fetcher::default(), sources)
+    }
+
+    pub(crate) fn with_fetcher(fetcher: Fetcher, sources: DataSources) -> Self {
+        Self { fetcher, sources }
+    }
+
+    pub fn with_concurrency(mut self, limit: usize) -> Self {
+        self.fetcher.set_concurrency(limit);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.fetcher.set_timeout(timeout);
+        self
+    }
+
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.fetcher.set_cache(cache);
+        self
+    }
+
+    async fn paginate<T: DeserializeOwned>(
+        &self,
+        name: &str,
+        request: impl Fn(Option<&str>) -> Result<surf::RequestBuilder>,
+    ) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        let mut page = None;
+        loop {
+            let body = self.fetcher.fetch(name, request(page.as_deref())?).await?;
+            let new: Page<T> = decode(name, &body).await?;
+            let done = new.items.is_empty() || new.next_page.is_none() || new.next_page == page;
+            items.extend(new.items);
+            if done {
+                return Ok(items);
+            }
+            debug!("Getting next page");
+            page = new.next_page;
+        }
+    }
+
+    pub async fn entities<T: DeserializeOwned>(
+        &self,
+        kind: EntityType,
+        query: &EntityQuery,
+    ) -> Result<Vec<Version<T>>> {
+        debug!("Getting {:?} entities", kind);
+        let url = self.sources.chronicler("v2/entities");
+        let id = join_ids(&query.ids);
+        self.paginate("entities", |page| {
+            self.fetcher
+                .client
+                .get(&url)
+                .query(&EntitiesParams {
+                    kind,
+                    id: id.clone(),
+                    at: query.at,
+                    page,
+                })
+                .map_err(|x| x.into_inner())
+        })
+        .await
+    }
+
+    pub async fn versions<T: DeserializeOwned>(
+        &self,
+        kind: EntityType,
+        query: &VersionQuery,
+    ) -> Result<Vec<Version<T>>> {
+        debug!("Getting {:?} versions", kind);
+        let url = self.sources.chronicler("v2/versions");
+        let id = join_ids(&query.ids);
+        self.paginate("versions", |page| {
+            self.fetcher
+                .client
+                .get(&url)
+                .query(&VersionsParams {
+                    kind,
+                    id: id.clone(),
+                    before: query.before,
+                    after: query.after,
+                    order: query.order,
+                    count: query.count,
+                    page,
+                })
+                .map_err(|x| x.into_inner())
+        })
+        .await
+    }
+
+    pub async fn players(&self) -> Result<Vec<Position>> {
+        let url = self.sources.chronicler("v1/players");
+        self.paginate("players", |page| {
+            self.fetcher
+                .client
+                .get(&url)
+                .query(&PlayersParams {
+                    forbidden: false,
+                    page,
+                })
+                .map_err(|x| x.into_inner())
+        })
+        .await
+    }
+
+    pub async fn games(&self, season: isize) -> Result<Vec<GameUpdate>> {
+        let url = self.sources.chronicler("v1/games");
+        self.paginate("past_games", |page| {
+            self.fetcher
+                .client
+                .get(&url)
+                .query(&GamesParams { season, page })
+                .map_err(|x| x.into_inner())
+        })
+        .await
+    }
+}
@@ -0,0 +1,69 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DataSources {
+    pub blaseball: String,
+    pub reference: String,
+    pub chronicler: String,
+}
+
+impl Default for DataSources {
+    fn default() -> Self {
+        Self {
+            blaseball: "https://www.blaseball.com".to_string(),
+            reference: "https://api.blaseball-reference.com".to_string(),
+            chronicler: "https://api.sibr.dev/chronicler".to_string(),
+        }
+    }
+}
+
+impl DataSources {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::open(path)?;
+        let buf = BufReader::new(file);
+        Ok(serde_json::from_reader(buf)?)
+    }
+
+    pub fn from_env() -> Result<Self> {
+        let mut sources = match env::var("DATA_SOURCES") {
+            Ok(path) => Self::from_file(path)?,
+            Err(_) => Self::default(),
+        };
+        if let Ok(url) = env::var("BLASEBALL_URL") {
+            sources.blaseball = url;
+        }
+        if let Ok(url) = env::var("REFERENCE_URL") {
+            sources.reference = url;
+        }
+        if let Ok(url) = env::var("CHRONICLER_URL") {
+            sources.chronicler = url;
+        }
+        Ok(sources)
+    }
+
+    fn join(base: &str, path: &str) -> String {
+        format!(
+            "{}/{}",
+            base.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        )
+    }
+
+    pub fn blaseball(&self, path: &str) -> String {
+        Self::join(&self.blaseball, path)
+    }
+
+    pub fn reference(&self, path: &str) -> String {
+        Self::join(&self.reference, path)
+    }
+
+    pub fn chronicler(&self, path: &str) -> String {
+        Self::join(&self.chronicler, path)
+    }
+}
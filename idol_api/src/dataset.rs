@@ -0,0 +1,42 @@
+use super::models::FeedEventType;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dataset {
+    Strikeouts,
+    AtBats,
+    PitcherStats,
+    BatterStats,
+    Teams,
+    Players,
+    Feed(FeedEventType),
+    PastGames,
+    Idols,
+}
+
+impl Dataset {
+    pub fn is_required(self) -> bool {
+        matches!(self, Self::Teams | Self::Players)
+    }
+}
+
+impl fmt::Display for Dataset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Strikeouts => write!(f, "strikeouts"),
+            Self::AtBats => write!(f, "at-bats"),
+            Self::PitcherStats => write!(f, "pitcher stats"),
+            Self::BatterStats => write!(f, "batter stats"),
+            Self::Teams => write!(f, "teams"),
+            Self::Players => write!(f, "players"),
+            Self::Feed(FeedEventType::Sun2Outcome) => write!(f, "Sun 2 events"),
+            Self::Feed(FeedEventType::BlackHoleOutcome) => write!(f, "Black Hole events"),
+            Self::Feed(FeedEventType::Unknown(kind)) => write!(f, "feed type {}", kind),
+            Self::Feed(kind) => write!(f, "{:?} events", kind),
+            Self::PastGames => write!(f, "past games"),
+            Self::Idols => write!(f, "idols"),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use super::error::DecodeError;
+use async_std::fs;
+use log::*;
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::env;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const MAX_SAVED_PAYLOADS: usize = 16;
+
+static SAVED_PAYLOADS: Lazy<Mutex<BTreeMap<String, usize>>> = Lazy::new(Default::default);
+
+fn payload_dir() -> PathBuf {
+    env::var("FAILED_PAYLOAD_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| env::temp_dir().join("idoleyes"))
+}
+
+async fn save_payload(name: &str, body: &[u8]) -> Option<PathBuf> {
+    let hash = format!("{:x}", Sha256::digest(body));
+    let dir = payload_dir();
+    let path = dir.join(format!("{}-{}.json", name.replace('/', "_"), &hash[..16]));
+    if fs::metadata(&path).await.is_ok() {
+        return Some(path);
+    }
+    {
+        let mut saved = SAVED_PAYLOADS.lock().unwrap();
+        let count = saved.entry(name.to_string()).or_default();
+        if *count >= MAX_SAVED_PAYLOADS {
+            debug!("Not saving {} payload, already saved {}", name, count);
+            return None;
+        }
+        *count += 1;
+    }
+    let result = match fs::create_dir_all(&dir).await {
+        Ok(_) => fs::write(&path, body).await,
+        Err(err) => Err(err),
+    };
+    match result {
+        Ok(_) => Some(path),
+        Err(err) => {
+            warn!("Couldn't save {} payload: {}", name, err);
+            None
+        }
+    }
+}
+
+pub async fn decode<T: DeserializeOwned>(name: &str, body: &[u8]) -> Result<T, DecodeError> {
+    let deserializer = &mut serde_json::Deserializer::from_slice(body);
+    match serde_path_to_error::deserialize(deserializer) {
+        Ok(x) => Ok(x),
+        Err(err) => Err(DecodeError {
+            name: name.to_string(),
+            path: err.path().to_string(),
+            saved: save_payload(name, body).await,
+            source: err.into_inner(),
+        }),
+    }
+}
@@ -0,0 +1,56 @@
+use super::dataset::Dataset;
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub struct StateError {
+    pub failures: Vec<(Dataset, anyhow::Error)>,
+}
+
+impl StateError {
+    pub fn datasets(&self) -> impl Iterator<Item = Dataset> + '_ {
+        self.failures.iter().map(|x| x.0)
+    }
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Couldn't build state")?;
+        for (i, (dataset, err)) in self.failures.iter().enumerate() {
+            let sep = if i == 0 { ": " } else { "; " };
+            write!(f, "{}couldn't get {} ({})", sep, dataset, err)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for StateError {}
+
+#[derive(Debug)]
+pub struct DecodeError {
+    pub name: String,
+    pub path: String,
+    pub saved: Option<PathBuf>,
+    pub source: serde_json::Error,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Couldn't parse {} at {}: {}",
+            self.name, self.path, self.source
+        )?;
+        if let Some(saved) = &self.saved {
+            write!(f, " (payload saved to {})", saved.display())?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for DecodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
@@ -0,0 +1,99 @@
+use super::cache::ResponseCache;
+use anyhow::{anyhow, ensure, Result};
+use async_lock::Semaphore;
+use async_std::future::timeout;
+use log::*;
+use std::sync::Arc;
+use std::time::Duration;
+use surf::StatusCode;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Fetcher {
+    pub client: surf::Client,
+    limit: Arc<Semaphore>,
+    timeout: Duration,
+    cache: Option<Arc<ResponseCache>>,
+}
+
+impl Default for Fetcher {
+    fn default() -> Self {
+        Self {
+            client: surf::Client::new(),
+            limit: Arc::new(Semaphore::new(4)),
+            timeout: Duration::from_secs(30),
+            cache: None,
+        }
+    }
+}
+
+impl Fetcher {
+    pub fn set_concurrency(&mut self, limit: usize) {
+        self.limit = Arc::new(Semaphore::new(limit.max(1)));
+    }
+
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    pub fn set_cache(&mut self, cache: ResponseCache) {
+        self.cache = Some(Arc::new(cache));
+    }
+
+    pub async fn fetch(&self, name: &str, request: surf::RequestBuilder) -> Result<Vec<u8>> {
+        let mut request = request.build();
+        let url = request.url().clone();
+        let cached = match &self.cache {
+            Some(cache) => cache.get(url.as_str()).await,
+            None => None,
+        };
+        if let (Some(cache), Some(entry)) = (&self.cache, &cached) {
+            if entry.is_fresh(cache.ttl(name)) {
+                debug!("Using cached {}", url);
+                return Ok(entry.body.clone());
+            }
+            entry.revalidate(&mut request);
+        }
+
+        let fetched = timeout(self.timeout, async {
+            let _permit = self.limit.acquire().await;
+            let mut resp = self
+                .client
+                .send(request)
+                .await
+                .map_err(|x| x.into_inner())?;
+            if resp.status() == StatusCode::NotModified && cached.is_some() {
+                return Ok(None);
+            }
+            ensure!(
+                resp.status().is_success(),
+                "Couldn't get {}: {}",
+                url,
+                resp.status()
+            );
+            let etag = resp.header("ETag").map(|x| x.as_str().to_string());
+            let last_modified = resp.header("Last-Modified").map(|x| x.as_str().to_string());
+            let body = resp.body_bytes().await.map_err(|x| x.into_inner())?;
+            Ok(Some((etag, last_modified, body)))
+        })
+        .await
+        .map_err(|_| anyhow!("Timed out getting {}", url))??;
+
+        match (&self.cache, fetched, cached) {
+            (Some(cache), None, Some(entry)) => {
+                debug!("{} not modified", url);
+                if let Err(err) = cache.touch(&entry).await {
+                    warn!("Couldn't update cache for {}: {}", url, err);
+                }
+                Ok(entry.body)
+            }
+            (Some(cache), Some((etag, last_modified, body)), _) => {
+                if let Err(err) = cache.store(url.as_str(), etag, last_modified, &body).await {
+                    warn!("Couldn't cache {}: {}", url, err);
+                }
+                Ok(body)
+            }
+            (_, Some((_, _, body)), _) => Ok(body),
+            (_, None, _) => Err(anyhow!("Couldn't get {}: not modified", url)),
+        }
+    }
+}
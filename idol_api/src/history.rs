@@ -0,0 +1,306 @@
+use super::chronicler::{Chronicler, EntityType, Order, Version, VersionQuery};
+use super::models::{
+    AtBatLeader, BattingStats, FeedEvent, FeedEventType, Game, GameUpdate, Idol, PitchingStats,
+    Player, PlayerId, Position, Simulation, StrikeoutLeader, Team,
+};
+use super::provider::DataProvider;
+use super::{Dataset, State, StateOptions};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::try_join;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// `<[T]>::partition_point` is newer than the pinned toolchain.
+fn partition_point<T>(items: &[T], mut pred: impl FnMut(&T) -> bool) -> usize {
+    items
+        .binary_search_by(|x| {
+            if pred(x) {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        })
+        .unwrap_or_else(|x| x)
+}
+
+#[derive(Debug, Clone)]
+pub struct Timeline<T> {
+    versions: Vec<Version<T>>,
+}
+
+impl<T> Default for Timeline<T> {
+    fn default() -> Self {
+        Self {
+            versions: Vec::new(),
+        }
+    }
+}
+
+impl<T> Timeline<T> {
+    pub fn new(mut versions: Vec<Version<T>>) -> Self {
+        versions.sort_by_key(|x| x.valid_from);
+        Self { versions }
+    }
+
+    pub fn versions(&self) -> &[Version<T>] {
+        &self.versions
+    }
+
+    pub fn version_at(&self, time: DateTime<Utc>) -> Option<&Version<T>> {
+        let idx = partition_point(&self.versions, |x| x.valid_from <= time);
+        let version = self.versions.get(idx.checked_sub(1)?)?;
+        match version.valid_to {
+            Some(valid_to) if valid_to <= time => None,
+            _ => Some(version),
+        }
+    }
+
+    pub fn at(&self, time: DateTime<Utc>) -> Option<&T> {
+        self.version_at(time).map(|x| &x.data)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Timelines<T> {
+    entities: HashMap<Uuid, Timeline<T>>,
+}
+
+impl<T> Default for Timelines<T> {
+    fn default() -> Self {
+        Self {
+            entities: HashMap::new(),
+        }
+    }
+}
+
+impl<T> Timelines<T> {
+    pub fn new(versions: impl IntoIterator<Item = Version<T>>) -> Self {
+        let mut grouped = HashMap::<Uuid, Vec<Version<T>>>::new();
+        for version in versions {
+            grouped.entry(version.entity_id).or_default().push(version);
+        }
+        Self {
+            entities: grouped
+                .into_iter()
+                .map(|(id, versions)| (id, Timeline::new(versions)))
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<&Timeline<T>> {
+        self.entities.get(&id)
+    }
+
+    pub fn at(&self, time: DateTime<Utc>) -> impl Iterator<Item = &T> {
+        self.entities.values().filter_map(move |x| x.at(time))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    pub players: Timelines<Player>,
+    pub teams: Timelines<Team>,
+    pub sim: Timeline<Simulation>,
+}
+
+impl History {
+    pub fn new(
+        players: Vec<Version<Player>>,
+        teams: Vec<Version<Team>>,
+        sim: Vec<Version<Simulation>>,
+    ) -> Self {
+        Self {
+            players: Timelines::new(players),
+            teams: Timelines::new(teams),
+            sim: Timeline::new(sim),
+        }
+    }
+
+    pub async fn fetch(chronicler: &Chronicler) -> Result<Self> {
+        let query = VersionQuery {
+            order: Some(Order::Asc),
+            count: Some(1000),
+            ..VersionQuery::default()
+        };
+        let (players, teams, sim) = try_join!(
+            chronicler.versions(EntityType::Player, &query),
+            chronicler.versions(EntityType::Team, &query),
+            chronicler.versions(EntityType::Sim, &query),
+        )?;
+        Ok(Self::new(players, teams, sim))
+    }
+
+    pub fn time_of(&self, season: isize, day: isize) -> Option<DateTime<Utc>> {
+        let versions = self.sim.versions();
+        let idx = partition_point(versions, |x| (x.data.season, x.data.day) < (season, day));
+        versions
+            .get(idx)
+            .filter(|x| (x.data.season, x.data.day) == (season, day))
+            .map(|x| x.valid_from)
+    }
+
+    pub fn players_at(&self, time: DateTime<Utc>) -> Vec<Position> {
+        self.players
+            .at(time)
+            .map(|x| Position {
+                id: x.id,
+                data: x.clone(),
+            })
+            .collect()
+    }
+
+    pub fn teams_at(&self, time: DateTime<Utc>) -> Vec<Team> {
+        self.teams.at(time).cloned().collect()
+    }
+
+    pub fn provider_at<'a, P: DataProvider + ?Sized>(
+        &'a self,
+        time: DateTime<Utc>,
+        inner: &'a P,
+    ) -> HistoricalProvider<'a, P> {
+        let sim = self.sim.at(time);
+        HistoricalProvider {
+            history: self,
+            time,
+            day: sim.map(|x| (x.season, x.day)),
+            inner,
+            stats: false,
+        }
+    }
+
+    pub async fn state_at(
+        &self,
+        time: DateTime<Utc>,
+        games: Vec<Game>,
+        season: isize,
+        inner: &(impl DataProvider + ?Sized),
+        options: &StateOptions,
+    ) -> Result<State> {
+        let provider = self.provider_at(time, inner);
+        Ok(State::from_games_and_season(games, season, &provider, options).await?)
+    }
+
+    pub async fn state_on(
+        &self,
+        season: isize,
+        day: isize,
+        inner: &(impl DataProvider + ?Sized),
+        options: &StateOptions,
+    ) -> Result<State> {
+        let time = self
+            .time_of(season, day)
+            .ok_or_else(|| anyhow!("No sim version for season {} day {}", season, day))?;
+        let games = inner
+            .past_games(season)
+            .await?
+            .into_iter()
+            .map(|x| x.data)
+            .filter(|x| x.day == day)
+            .collect();
+        self.state_at(time, games, season, inner, options).await
+    }
+}
+
+pub struct HistoricalProvider<'a, P: ?Sized> {
+    history: &'a History,
+    time: DateTime<Utc>,
+    day: Option<(isize, isize)>,
+    inner: &'a P,
+    stats: bool,
+}
+
+impl<'a, P: ?Sized> HistoricalProvider<'a, P> {
+    // Only for providers whose stats, idols and feed are already complete as of `time`;
+    // otherwise they're reported as missing rather than leaking the current values into
+    // the past or silently dropping feed events older than the upstream's latest page.
+    pub fn with_stats(self) -> Self {
+        Self {
+            stats: true,
+            ..self
+        }
+    }
+
+    fn check(&self, dataset: Dataset) -> Result<()> {
+        if self.stats {
+            Ok(())
+        } else {
+            Err(anyhow!("No historical {} at {}", dataset, self.time))
+        }
+    }
+
+    fn is_past(&self, season: isize, day: isize) -> bool {
+        match self.day {
+            Some(x) => (season, day) < x,
+            None => true,
+        }
+    }
+}
+
+#[async_trait]
+impl<'a, P: DataProvider + ?Sized> DataProvider for HistoricalProvider<'a, P> {
+    async fn strikeout_leaders(&self, season: isize) -> Result<Vec<StrikeoutLeader>> {
+        self.check(Dataset::Strikeouts)?;
+        self.inner.strikeout_leaders(season).await
+    }
+
+    async fn at_bat_leaders(&self, season: isize) -> Result<Vec<AtBatLeader>> {
+        self.check(Dataset::AtBats)?;
+        self.inner.at_bat_leaders(season).await
+    }
+
+    async fn pitcher_stats(
+        &self,
+        player_ids: &[PlayerId],
+        season: isize,
+    ) -> Result<Vec<PitchingStats>> {
+        self.check(Dataset::PitcherStats)?;
+        self.inner.pitcher_stats(player_ids, season).await
+    }
+
+    async fn batter_stats(
+        &self,
+        player_ids: &[PlayerId],
+        season: isize,
+    ) -> Result<Vec<BattingStats>> {
+        self.check(Dataset::BatterStats)?;
+        self.inner.batter_stats(player_ids, season).await
+    }
+
+    async fn teams(&self) -> Result<Vec<Team>> {
+        Ok(self.history.teams_at(self.time))
+    }
+
+    async fn players(&self) -> Result<Vec<Position>> {
+        Ok(self.history.players_at(self.time))
+    }
+
+    async fn feed_events(
+        &self,
+        kind: FeedEventType,
+        limit: Option<usize>,
+    ) -> Result<Vec<FeedEvent>> {
+        self.check(Dataset::Feed(kind))?;
+        let events = self.inner.feed_events(kind, None).await?;
+        Ok(events
+            .into_iter()
+            .filter(|x| self.is_past(x.season, x.day))
+            .take(limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    async fn past_games(&self, season: isize) -> Result<Vec<GameUpdate>> {
+        let games = self.inner.past_games(season).await?;
+        Ok(games
+            .into_iter()
+            .filter(|x| self.is_past(x.data.season, x.data.day))
+            .collect())
+    }
+
+    async fn idols(&self) -> Result<Vec<Idol>> {
+        self.check(Dataset::Idols)?;
+        self.inner.idols().await
+    }
+}
@@ -0,0 +1,45 @@
+use super::models::{PlayerId, TeamId};
+use super::State;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Debug, Default)]
+pub(crate) struct Index {
+    pub players: HashMap<PlayerId, usize>,
+    pub teams: HashMap<TeamId, usize>,
+    pub pitcher_stats: HashMap<PlayerId, usize>,
+    pub batter_stats: HashMap<PlayerId, usize>,
+    pub strikeouts: HashMap<PlayerId, usize>,
+    pub at_bats: HashMap<PlayerId, usize>,
+    pub past_games: HashMap<(isize, isize, TeamId), usize>,
+}
+
+fn index_by<T, K: Eq + Hash>(items: &[T], key: impl Fn(&T) -> K) -> HashMap<K, usize> {
+    let mut map = HashMap::with_capacity(items.len());
+    for (i, item) in items.iter().enumerate() {
+        map.entry(key(item)).or_insert(i);
+    }
+    map
+}
+
+impl Index {
+    pub fn build(state: &State) -> Self {
+        let mut past_games = HashMap::with_capacity(state.past_games().len() * 2);
+        for (i, update) in state.past_games().iter().enumerate() {
+            let game = &update.data;
+            for team in game.team_ids() {
+                past_games.entry((game.season, game.day, team)).or_insert(i);
+            }
+        }
+
+        Self {
+            players: index_by(state.players(), |x| x.id),
+            teams: index_by(state.teams(), |x| x.id),
+            pitcher_stats: index_by(state.pitcher_stats(), |x| x.player_id),
+            batter_stats: index_by(state.batter_stats(), |x| x.player_id),
+            strikeouts: index_by(state.strikeouts(), |x| x.player_id),
+            at_bats: index_by(state.at_bats(), |x| x.player_id),
+            past_games,
+        }
+    }
+}
@@ -1,6 +1,24 @@
+mod cache;
+pub mod chronicler;
+mod data_sources;
+mod dataset;
+mod decode;
+mod error;
+mod fetcher;
+pub mod history;
+mod ids;
+mod index;
 mod model_calls;
 pub mod models;
+pub mod provider;
+mod serde_helpers;
+pub mod snapshot;
 mod state;
 pub mod team_pair;
 
-pub use state::State;
+pub use cache::ResponseCache;
+pub use data_sources::DataSources;
+pub use dataset::Dataset;
+pub use decode::decode;
+pub use error::{DecodeError, StateError};
+pub use state::{FeedRequest, FetchMode, State, StateOptions};
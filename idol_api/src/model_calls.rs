@@ -1,12 +1,72 @@
-use super::models::{FeedItem, Game, PitchingStats, Position, Team};
+use super::models::{
+    BattingStats, Event, FeedEvent, FeedEventType, Game, GameId, Games, Phase, PitchingStats,
+    Player, PlayerId, Position, Team, TeamId, Weather,
+};
 use super::team_pair::{TeamPair, Transpose};
 use super::State;
+use log::*;
+
+impl Phase {
+    pub fn is_regular_season(self) -> bool {
+        matches!(self, Self::Earlseason | Self::Midseason | Self::Lateseason)
+    }
+
+    pub fn is_postseason(self) -> bool {
+        matches!(
+            self,
+            Self::Earlpostseason | Self::Latepostseason | Self::Postseasonend
+        )
+    }
+}
+
+impl Games {
+    pub fn betting_open(&self) -> bool {
+        self.sim.phase.is_regular_season()
+            || (self.sim.phase.is_postseason() && !self.tomorrow_schedule.is_empty())
+    }
+}
+
+impl Event {
+    pub fn upcoming_games(&self) -> Vec<Game> {
+        if self.value.games.tomorrow_schedule.is_empty() {
+            warn!("No games scheduled, checking current games");
+            self.value.games.schedule.clone()
+        } else {
+            self.value.games.tomorrow_schedule.clone()
+        }
+    }
+}
+
+impl Weather {
+    pub fn is_known(self) -> bool {
+        !matches!(self, Self::Unknown(_))
+    }
+
+    pub fn can_replace_pitcher(self) -> bool {
+        matches!(self, Self::SolarEclipse | Self::Feedback | Self::Reverb)
+    }
+}
+
+impl Player {
+    pub fn modifications(&self) -> impl Iterator<Item = &str> {
+        self.perm_attr
+            .iter()
+            .chain(&self.seas_attr)
+            .chain(&self.week_attr)
+            .chain(&self.game_attr)
+            .map(String::as_str)
+    }
+
+    pub fn has_modification(&self, modification: &str) -> bool {
+        self.modifications().any(|x| x == modification)
+    }
+}
 
 impl Game {
-    pub fn pitcher_ids(&self) -> Option<TeamPair<&str>> {
+    pub fn pitcher_ids(&self) -> Option<TeamPair<PlayerId>> {
         Some(TeamPair {
-            home: &self.home_pitcher.as_ref()?,
-            away: &self.away_pitcher.as_ref()?,
+            home: self.home_pitcher?,
+            away: self.away_pitcher?,
         })
     }
 
@@ -17,58 +77,85 @@ impl Game {
         })
     }
 
-    pub fn team_ids(&self) -> TeamPair<&str> {
+    pub fn team_ids(&self) -> TeamPair<TeamId> {
         TeamPair {
-            home: &self.home_team,
-            away: &self.away_team,
+            home: self.home_team,
+            away: self.away_team,
+        }
+    }
+
+    pub fn is_special(&self) -> bool {
+        self.is_title_match || self.is_prize_match
+    }
+
+    pub fn strikes(&self) -> TeamPair<Option<isize>> {
+        TeamPair {
+            home: self.home_strikes,
+            away: self.away_strikes,
+        }
+    }
+
+    pub fn outs(&self) -> TeamPair<Option<isize>> {
+        TeamPair {
+            home: self.home_outs,
+            away: self.away_outs,
         }
     }
 
     pub fn teams<'a>(&self, state: &'a State) -> Option<TeamPair<&'a Team>> {
-        self.team_ids()
-            .and_then(|x| state.teams.iter().find(|y| x == y.id))
+        self.team_ids().and_then(|x| state.team(x))
     }
 
     pub fn pitcher_positions<'a>(&self, state: &'a State) -> Option<TeamPair<&'a Position>> {
-        self.pitcher_ids()?
-            .and_then(|x| state.players.iter().find(|y| x == y.id))
+        self.pitcher_ids()?.and_then(|x| state.player(x))
     }
 
     pub fn pitcher_stats<'a>(&self, state: &'a State) -> TeamPair<Option<&'a PitchingStats>> {
         self.pitcher_ids()
-            .and_then(|x| x.and_then(|x| state.pitcher_stats.iter().find(|y| x == y.player_id)))
+            .and_then(|x| x.and_then(|x| state.pitching_stats(x)))
             .transpose()
     }
 }
 
 impl Team {
     pub fn at_bats<'a>(&'a self, state: &'a State) -> impl Iterator<Item = Option<usize>> + 'a {
-        self.lineup.iter().map(move |x| {
-            state
-                .at_bats
-                .iter()
-                .find(|y| x == &y.player_id)
-                .map(|y| y.at_bats)
-        })
+        self.lineup
+            .iter()
+            .map(move |x| state.at_bat_leader(*x).map(|y| y.at_bats))
     }
 
     pub fn strikeouts<'a>(&'a self, state: &'a State) -> impl Iterator<Item = Option<usize>> + 'a {
-        self.lineup.iter().map(move |x| {
-            state
-                .strikeouts
-                .iter()
-                .find(|y| x == &y.player_id)
-                .map(|y| y.strikeouts)
-        })
+        self.lineup
+            .iter()
+            .map(move |x| state.strikeout_leader(*x).map(|y| y.strikeouts))
+    }
+
+    pub fn batting_stats<'a>(
+        &'a self,
+        state: &'a State,
+    ) -> impl Iterator<Item = Option<&'a BattingStats>> + 'a {
+        self.lineup.iter().map(move |x| state.batting_stats(*x))
     }
 }
 
-impl FeedItem {
+impl FeedEvent {
+    pub fn is(&self, kind: FeedEventType) -> bool {
+        self.kind == kind
+    }
+
+    pub fn tags_player(&self, id: PlayerId) -> bool {
+        self.player_tags.contains(&id)
+    }
+
+    pub fn tags_team(&self, id: TeamId) -> bool {
+        self.team_tags.contains(&id)
+    }
+
+    pub fn tags_game(&self, id: GameId) -> bool {
+        self.game_tags.contains(&id)
+    }
+
     pub fn game<'a>(&self, state: &'a State) -> Option<&'a Game> {
-        state.past_games.iter().map(|x| &x.data).find(|game| {
-            self.day == game.day
-                && self.season == game.season
-                && game.team_ids().any(|team_id| self.team_tags[0] == team_id)
-        })
+        state.past_game(self.season, self.day, *self.team_tags.first()?)
     }
 }
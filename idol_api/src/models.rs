@@ -1,25 +1,85 @@
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+pub use super::ids::{GameId, PlayerId, TeamId};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PitchingStats {
-    pub player_id: String,
+    pub player_id: PlayerId,
     #[serde(with = "serde_with::rust::display_fromstr")]
     pub strikeouts_per_9: f64,
     #[serde(with = "serde_with::rust::display_fromstr")]
     pub games: usize,
+    #[serde(default)]
+    #[serde(with = "serde_with::rust::display_fromstr")]
+    pub innings: f64,
+    #[serde(default)]
+    #[serde(with = "serde_with::rust::display_fromstr")]
+    pub strikeouts: usize,
+    #[serde(default)]
+    #[serde(with = "serde_with::rust::display_fromstr")]
+    pub walks: usize,
+    #[serde(default)]
+    #[serde(with = "serde_with::rust::display_fromstr")]
+    pub hits_allowed: usize,
+    #[serde(default)]
+    #[serde(with = "crate::serde_helpers::empty_as_none")]
+    pub earned_run_average: Option<f64>,
+    #[serde(default)]
+    #[serde(with = "crate::serde_helpers::empty_as_none")]
+    pub walks_and_hits_per_inning: Option<f64>,
+    #[serde(default)]
+    #[serde(with = "crate::serde_helpers::empty_as_none")]
+    pub strikeouts_per_walk: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BattingStats {
+    pub player_id: PlayerId,
+    #[serde(default)]
+    #[serde(with = "serde_with::rust::display_fromstr")]
+    pub games: usize,
+    #[serde(default)]
+    #[serde(with = "serde_with::rust::display_fromstr")]
+    pub plate_appearances: usize,
+    #[serde(default)]
+    #[serde(with = "serde_with::rust::display_fromstr")]
+    pub at_bats: usize,
+    #[serde(default)]
+    #[serde(with = "serde_with::rust::display_fromstr")]
+    pub hits: usize,
+    #[serde(default)]
+    #[serde(with = "serde_with::rust::display_fromstr")]
+    pub home_runs: usize,
+    #[serde(default)]
+    #[serde(with = "serde_with::rust::display_fromstr")]
+    pub stolen_bases: usize,
+    #[serde(default)]
+    #[serde(with = "serde_with::rust::display_fromstr")]
+    pub strikeouts: usize,
+    #[serde(default)]
+    #[serde(with = "serde_with::rust::display_fromstr")]
+    pub walks: usize,
+    #[serde(default)]
+    #[serde(with = "crate::serde_helpers::empty_as_none")]
+    pub batting_average: Option<f64>,
+    #[serde(default)]
+    #[serde(with = "crate::serde_helpers::empty_as_none")]
+    pub on_base_percentage: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StrikeoutLeader {
-    pub player_id: String,
+    pub player_id: PlayerId,
     #[serde(with = "serde_with::rust::display_fromstr")]
     pub strikeouts: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AtBatLeader {
-    pub player_id: String,
+    pub player_id: PlayerId,
     #[serde(with = "serde_with::rust::display_fromstr")]
     pub at_bats: usize,
 }
@@ -27,43 +87,220 @@ pub struct AtBatLeader {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Player {
-    pub id: String,
+    pub id: PlayerId,
     pub name: String,
     pub ruthlessness: f64,
     pub patheticism: f64,
+    pub overpowerment: Option<f64>,
+    pub unthwackability: Option<f64>,
+    pub shakespearianism: Option<f64>,
+    pub suppression: Option<f64>,
+    pub coldness: Option<f64>,
+    pub thwackability: Option<f64>,
+    pub buoyancy: Option<f64>,
+    pub divinity: Option<f64>,
+    pub moxie: Option<f64>,
+    pub musclitude: Option<f64>,
+    pub martyrdom: Option<f64>,
+    pub tragicness: Option<f64>,
+    pub base_thirst: Option<f64>,
+    pub continuation: Option<f64>,
+    pub ground_friction: Option<f64>,
+    pub indulgence: Option<f64>,
+    pub laserlikeness: Option<f64>,
+    pub anticapitalism: Option<f64>,
+    pub chasiness: Option<f64>,
+    pub omniscience: Option<f64>,
+    pub tenaciousness: Option<f64>,
+    pub watchfulness: Option<f64>,
+    pub pressurization: Option<f64>,
+    pub cinnamon: Option<f64>,
+    pub pitching_rating: Option<f64>,
+    pub hitting_rating: Option<f64>,
+    pub baserunning_rating: Option<f64>,
+    pub defense_rating: Option<f64>,
     #[serde(default)]
-    pub pitching_rating: f64,
+    pub perm_attr: Vec<String>,
     #[serde(default)]
-    pub hitting_rating: f64,
+    pub seas_attr: Vec<String>,
     #[serde(default)]
-    #[serde(with = "serde_with::rust::default_on_null")]
-    pub league_team_id: String,
+    pub week_attr: Vec<String>,
+    #[serde(default)]
+    pub game_attr: Vec<String>,
+    #[serde(default)]
+    #[serde(with = "crate::serde_helpers::empty_as_none")]
+    pub league_team_id: Option<TeamId>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Position {
-    pub id: String,
+    pub id: PlayerId,
     pub data: Player,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Game {
-    pub id: String,
-    pub away_pitcher: Option<String>,
+    pub id: GameId,
+    #[serde(default)]
+    #[serde(with = "crate::serde_helpers::empty_as_none")]
+    pub away_pitcher: Option<PlayerId>,
     pub away_pitcher_name: Option<String>,
-    pub home_pitcher: Option<String>,
+    #[serde(default)]
+    #[serde(with = "crate::serde_helpers::empty_as_none")]
+    pub home_pitcher: Option<PlayerId>,
     pub home_pitcher_name: Option<String>,
-    pub away_team: Cow<'static, str>,
+    pub away_team: TeamId,
     pub away_team_name: Cow<'static, str>,
-    pub home_team: String,
+    pub home_team: TeamId,
     pub home_team_name: Cow<'static, str>,
     pub away_odds: f64,
     pub home_odds: f64,
+    #[serde(default)]
     pub inning: isize,
     pub day: isize,
     pub season: isize,
+    #[serde(default)]
+    pub weather: Option<Weather>,
+    #[serde(default)]
+    pub stadium_id: Option<String>,
+    #[serde(default)]
+    pub series_index: isize,
+    #[serde(default)]
+    pub series_length: isize,
+    #[serde(default)]
+    pub is_postseason: bool,
+    #[serde(default)]
+    pub is_title_match: bool,
+    #[serde(default)]
+    pub is_prize_match: bool,
+    #[serde(default)]
+    pub home_strikes: Option<isize>,
+    #[serde(default)]
+    pub away_strikes: Option<isize>,
+    #[serde(default)]
+    pub home_balls: Option<isize>,
+    #[serde(default)]
+    pub away_balls: Option<isize>,
+    #[serde(default)]
+    pub home_bases: Option<isize>,
+    #[serde(default)]
+    pub away_bases: Option<isize>,
+    #[serde(default)]
+    pub home_outs: Option<isize>,
+    #[serde(default)]
+    pub away_outs: Option<isize>,
+    #[serde(default)]
+    pub outcomes: Vec<String>,
+}
+
+/// Compares enums by their numeric code, so an `Unknown` holding a known
+/// code is the same as the named variant.
+macro_rules! compare_by_code {
+    ($name:ident) => {
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                isize::from(*self) == isize::from(*other)
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl Hash for $name {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                isize::from(*self).hash(state);
+            }
+        }
+    };
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(from = "isize", into = "isize")]
+pub enum Weather {
+    Void,
+    Sun2,
+    Overcast,
+    Rainy,
+    Sandstorm,
+    Snowy,
+    Acidic,
+    SolarEclipse,
+    Glitter,
+    Blooddrain,
+    Peanuts,
+    Birds,
+    Feedback,
+    Reverb,
+    BlackHole,
+    Coffee,
+    Coffee2,
+    Coffee3s,
+    Flooding,
+    Salmon,
+    PolarityPlus,
+    PolarityMinus,
+    Unknown(isize),
+}
+
+impl From<isize> for Weather {
+    fn from(weather: isize) -> Self {
+        match weather {
+            0 => Self::Void,
+            1 => Self::Sun2,
+            2 => Self::Overcast,
+            3 => Self::Rainy,
+            4 => Self::Sandstorm,
+            5 => Self::Snowy,
+            6 => Self::Acidic,
+            7 => Self::SolarEclipse,
+            8 => Self::Glitter,
+            9 => Self::Blooddrain,
+            10 => Self::Peanuts,
+            11 => Self::Birds,
+            12 => Self::Feedback,
+            13 => Self::Reverb,
+            14 => Self::BlackHole,
+            15 => Self::Coffee,
+            16 => Self::Coffee2,
+            17 => Self::Coffee3s,
+            18 => Self::Flooding,
+            19 => Self::Salmon,
+            20 => Self::PolarityPlus,
+            21 => Self::PolarityMinus,
+            x => Self::Unknown(x),
+        }
+    }
+}
+
+impl From<Weather> for isize {
+    fn from(weather: Weather) -> Self {
+        match weather {
+            Weather::Void => 0,
+            Weather::Sun2 => 1,
+            Weather::Overcast => 2,
+            Weather::Rainy => 3,
+            Weather::Sandstorm => 4,
+            Weather::Snowy => 5,
+            Weather::Acidic => 6,
+            Weather::SolarEclipse => 7,
+            Weather::Glitter => 8,
+            Weather::Blooddrain => 9,
+            Weather::Peanuts => 10,
+            Weather::Birds => 11,
+            Weather::Feedback => 12,
+            Weather::Reverb => 13,
+            Weather::BlackHole => 14,
+            Weather::Coffee => 15,
+            Weather::Coffee2 => 16,
+            Weather::Coffee3s => 17,
+            Weather::Flooding => 18,
+            Weather::Salmon => 19,
+            Weather::PolarityPlus => 20,
+            Weather::PolarityMinus => 21,
+            Weather::Unknown(x) => x,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -71,14 +308,81 @@ pub struct Game {
 pub struct Simulation {
     pub season: isize,
     pub day: isize,
-    pub phase: isize,
+    pub phase: Phase,
+}
+
+compare_by_code!(Weather);
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(from = "isize", into = "isize")]
+pub enum Phase {
+    Rest,
+    Preseason,
+    Earlseason,
+    Earlsiesta,
+    Midseason,
+    Latesiesta,
+    Lateseason,
+    Endseason,
+    Prepostseason,
+    Earlpostseason,
+    Latepostseason,
+    Postseasonend,
+    Election,
+    Unknown(isize),
+}
+
+impl From<isize> for Phase {
+    fn from(phase: isize) -> Self {
+        match phase {
+            0 => Self::Rest,
+            1 => Self::Preseason,
+            2 => Self::Earlseason,
+            3 => Self::Earlsiesta,
+            4 => Self::Midseason,
+            5 => Self::Latesiesta,
+            6 => Self::Lateseason,
+            7 => Self::Endseason,
+            8 => Self::Prepostseason,
+            9 => Self::Earlpostseason,
+            10 => Self::Latepostseason,
+            11 => Self::Postseasonend,
+            12 => Self::Election,
+            x => Self::Unknown(x),
+        }
+    }
+}
+
+impl From<Phase> for isize {
+    fn from(phase: Phase) -> Self {
+        match phase {
+            Phase::Rest => 0,
+            Phase::Preseason => 1,
+            Phase::Earlseason => 2,
+            Phase::Earlsiesta => 3,
+            Phase::Midseason => 4,
+            Phase::Latesiesta => 5,
+            Phase::Lateseason => 6,
+            Phase::Endseason => 7,
+            Phase::Prepostseason => 8,
+            Phase::Earlpostseason => 9,
+            Phase::Latepostseason => 10,
+            Phase::Postseasonend => 11,
+            Phase::Election => 12,
+            Phase::Unknown(x) => x,
+        }
+    }
 }
 
+compare_by_code!(Phase);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Games {
     pub sim: Simulation,
+    #[serde(default)]
     pub schedule: Vec<Game>,
+    #[serde(default)]
     pub tomorrow_schedule: Vec<Game>,
 }
 
@@ -95,19 +399,24 @@ pub struct Event {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Team {
-    pub id: String,
+    pub id: TeamId,
     pub full_name: Cow<'static, str>,
-    pub lineup: Vec<String>,
-    pub rotation: Vec<String>,
-    pub bullpen: Vec<String>,
-    pub bench: Vec<String>,
+    #[serde(default)]
+    pub lineup: Vec<PlayerId>,
+    #[serde(default)]
+    pub rotation: Vec<PlayerId>,
+    #[serde(default)]
+    pub bullpen: Vec<PlayerId>,
+    #[serde(default)]
+    pub bench: Vec<PlayerId>,
+    #[serde(default)]
     pub perm_attr: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct Idol {
-    pub player_id: String,
+    pub player_id: PlayerId,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -129,8 +438,163 @@ pub struct GameUpdate {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct FeedItem {
-    pub team_tags: [String; 1],
+pub struct FeedEvent {
+    #[serde(rename = "type")]
+    pub kind: FeedEventType,
+    #[serde(default)]
+    pub player_tags: Vec<PlayerId>,
+    #[serde(default)]
+    pub team_tags: Vec<TeamId>,
+    #[serde(default)]
+    pub game_tags: Vec<GameId>,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub metadata: serde_json::Value,
     pub day: isize,
     pub season: isize,
 }
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(from = "isize", into = "isize")]
+pub enum FeedEventType {
+    LetsGo,
+    PlayBall,
+    HalfInning,
+    PitcherChange,
+    StolenBase,
+    Walk,
+    Strikeout,
+    FlyOut,
+    GroundOut,
+    HomeRun,
+    Hit,
+    GameEnd,
+    PlateAppearance,
+    Strike,
+    Ball,
+    FoulBall,
+    ShamingRun,
+    HomeFieldAdvantage,
+    HitByPitch,
+    BatterSkipped,
+    Party,
+    StrikeZapped,
+    WeatherChange,
+    MildPitch,
+    InningEnd,
+    BigDeal,
+    BlackHole,
+    Sun2,
+    BirdsCircle,
+    BirdsUnshell,
+    Feedback,
+    Reverb,
+    Incineration,
+    FireproofIncineration,
+    Sun2Outcome,
+    BlackHoleOutcome,
+    Unknown(isize),
+}
+
+impl From<isize> for FeedEventType {
+    fn from(kind: isize) -> Self {
+        match kind {
+            0 => Self::LetsGo,
+            1 => Self::PlayBall,
+            2 => Self::HalfInning,
+            3 => Self::PitcherChange,
+            4 => Self::StolenBase,
+            5 => Self::Walk,
+            6 => Self::Strikeout,
+            7 => Self::FlyOut,
+            8 => Self::GroundOut,
+            9 => Self::HomeRun,
+            10 => Self::Hit,
+            11 => Self::GameEnd,
+            12 => Self::PlateAppearance,
+            13 => Self::Strike,
+            14 => Self::Ball,
+            15 => Self::FoulBall,
+            20 => Self::ShamingRun,
+            21 => Self::HomeFieldAdvantage,
+            22 => Self::HitByPitch,
+            23 => Self::BatterSkipped,
+            24 => Self::Party,
+            25 => Self::StrikeZapped,
+            26 => Self::WeatherChange,
+            27 => Self::MildPitch,
+            28 => Self::InningEnd,
+            29 => Self::BigDeal,
+            30 => Self::BlackHole,
+            31 => Self::Sun2,
+            33 => Self::BirdsCircle,
+            35 => Self::BirdsUnshell,
+            41 => Self::Feedback,
+            47 => Self::Reverb,
+            54 => Self::Incineration,
+            55 => Self::FireproofIncineration,
+            156 => Self::Sun2Outcome,
+            157 => Self::BlackHoleOutcome,
+            x => Self::Unknown(x),
+        }
+    }
+}
+
+impl From<FeedEventType> for isize {
+    fn from(kind: FeedEventType) -> Self {
+        match kind {
+            FeedEventType::LetsGo => 0,
+            FeedEventType::PlayBall => 1,
+            FeedEventType::HalfInning => 2,
+            FeedEventType::PitcherChange => 3,
+            FeedEventType::StolenBase => 4,
+            FeedEventType::Walk => 5,
+            FeedEventType::Strikeout => 6,
+            FeedEventType::FlyOut => 7,
+            FeedEventType::GroundOut => 8,
+            FeedEventType::HomeRun => 9,
+            FeedEventType::Hit => 10,
+            FeedEventType::GameEnd => 11,
+            FeedEventType::PlateAppearance => 12,
+            FeedEventType::Strike => 13,
+            FeedEventType::Ball => 14,
+            FeedEventType::FoulBall => 15,
+            FeedEventType::ShamingRun => 20,
+            FeedEventType::HomeFieldAdvantage => 21,
+            FeedEventType::HitByPitch => 22,
+            FeedEventType::BatterSkipped => 23,
+            FeedEventType::Party => 24,
+            FeedEventType::StrikeZapped => 25,
+            FeedEventType::WeatherChange => 26,
+            FeedEventType::MildPitch => 27,
+            FeedEventType::InningEnd => 28,
+            FeedEventType::BigDeal => 29,
+            FeedEventType::BlackHole => 30,
+            FeedEventType::Sun2 => 31,
+            FeedEventType::BirdsCircle => 33,
+            FeedEventType::BirdsUnshell => 35,
+            FeedEventType::Feedback => 41,
+            FeedEventType::Reverb => 47,
+            FeedEventType::Incineration => 54,
+            FeedEventType::FireproofIncineration => 55,
+            FeedEventType::Sun2Outcome => 156,
+            FeedEventType::BlackHoleOutcome => 157,
+            FeedEventType::Unknown(x) => x,
+        }
+    }
+}
+
+compare_by_code!(FeedEventType);
+
+impl PartialOrd for FeedEventType {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FeedEventType {
+    fn cmp(&self, other: &Self) -> Ordering {
+        isize::from(*self).cmp(&isize::from(*other))
+    }
+}
@@ -0,0 +1,358 @@
+use super::chronicler::Chronicler;
+use super::data_sources::DataSources;
+use super::dataset::Dataset;
+use super::decode::decode;
+use super::fetcher::Fetcher;
+use super::models::{
+    AtBatLeader, BattingStats, FeedEvent, FeedEventType, GameUpdate, Idol, Idols, PitchingStats,
+    PlayerId, Position, StrikeoutLeader, Team,
+};
+use super::ResponseCache;
+use anyhow::{ensure, Result};
+use async_trait::async_trait;
+use log::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[async_trait]
+pub trait DataProvider: Send + Sync {
+    async fn strikeout_leaders(&self, season: isize) -> Result<Vec<StrikeoutLeader>>;
+    async fn at_bat_leaders(&self, season: isize) -> Result<Vec<AtBatLeader>>;
+    async fn pitcher_stats(
+        &self,
+        player_ids: &[PlayerId],
+        season: isize,
+    ) -> Result<Vec<PitchingStats>>;
+    async fn batter_stats(
+        &self,
+        player_ids: &[PlayerId],
+        season: isize,
+    ) -> Result<Vec<BattingStats>>;
+    async fn teams(&self) -> Result<Vec<Team>>;
+    async fn players(&self) -> Result<Vec<Position>>;
+    async fn feed_events(
+        &self,
+        kind: FeedEventType,
+        limit: Option<usize>,
+    ) -> Result<Vec<FeedEvent>>;
+    async fn past_games(&self, season: isize) -> Result<Vec<GameUpdate>>;
+    async fn idols(&self) -> Result<Vec<Idol>>;
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpProvider {
+    fetcher: Fetcher,
+    sources: DataSources,
+}
+
+impl HttpProvider {
+    pub fn new(sources: DataSources) -> Self {
+        Self {
+            fetcher: Fetcher::default(),
+            sources,
+        }
+    }
+
+    pub fn with_concurrency(mut self, limit: usize) -> Self {
+        self.fetcher.set_concurrency(limit);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.fetcher.set_timeout(timeout);
+        self
+    }
+
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.fetcher.set_cache(cache);
+        self
+    }
+
+    pub fn sources(&self) -> &DataSources {
+        &self.sources
+    }
+
+    pub fn chronicler(&self) -> Chronicler {
+        Chronicler::with_fetcher(self.fetcher.clone(), self.sources.clone())
+    }
+
+    async fn player_stats<T: DeserializeOwned>(
+        &self,
+        name: &str,
+        category: &'static str,
+        player_ids: &[PlayerId],
+        season: isize,
+    ) -> Result<Vec<T>> {
+        let body = self
+            .fetcher
+            .fetch(
+                name,
+                self.fetcher
+                    .client
+                    .get(self.sources.reference("v1/playerStats"))
+                    .query(&StatsQuery {
+                        category,
+                        player_ids: player_ids
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(","),
+                        season,
+                    })
+                    .map_err(|x| x.into_inner())?,
+            )
+            .await?;
+        Ok(decode(name, &body).await?)
+    }
+}
+
+#[derive(Serialize)]
+struct LeadersQuery {
+    category: &'static str,
+    stat: &'static str,
+    season: isize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StatsQuery {
+    category: &'static str,
+    player_ids: String,
+    season: isize,
+}
+
+#[derive(Serialize)]
+struct FeedQuery {
+    #[serde(rename = "type")]
+    kind: FeedEventType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
+}
+
+#[async_trait]
+impl DataProvider for HttpProvider {
+    async fn strikeout_leaders(&self, season: isize) -> Result<Vec<StrikeoutLeader>> {
+        debug!("Getting batter strikeouts");
+        let body = self
+            .fetcher
+            .fetch(
+                "strikeouts",
+                self.fetcher
+                    .client
+                    .get(self.sources.reference("v1/seasonLeaders"))
+                    .query(&LeadersQuery {
+                        category: "batting",
+                        stat: "strikeouts",
+                        season,
+                    })
+                    .map_err(|x| x.into_inner())?,
+            )
+            .await?;
+        Ok(decode("strikeouts", &body).await?)
+    }
+
+    async fn at_bat_leaders(&self, season: isize) -> Result<Vec<AtBatLeader>> {
+        debug!("Getting at-bats");
+        let body = self
+            .fetcher
+            .fetch(
+                "at_bats",
+                self.fetcher
+                    .client
+                    .get(self.sources.reference("v1/seasonLeaders"))
+                    .query(&LeadersQuery {
+                        category: "batting",
+                        stat: "at_bats",
+                        season,
+                    })
+                    .map_err(|x| x.into_inner())?,
+            )
+            .await?;
+        Ok(decode("at_bats", &body).await?)
+    }
+
+    async fn pitcher_stats(
+        &self,
+        player_ids: &[PlayerId],
+        season: isize,
+    ) -> Result<Vec<PitchingStats>> {
+        debug!("Getting pitcher stats");
+        self.player_stats("pitcher_stats", "pitching", player_ids, season)
+            .await
+    }
+
+    async fn batter_stats(
+        &self,
+        player_ids: &[PlayerId],
+        season: isize,
+    ) -> Result<Vec<BattingStats>> {
+        debug!("Getting batter stats");
+        self.player_stats("batter_stats", "batting", player_ids, season)
+            .await
+    }
+
+    async fn teams(&self) -> Result<Vec<Team>> {
+        debug!("Getting teams");
+        let body = self
+            .fetcher
+            .fetch(
+                "teams",
+                self.fetcher
+                    .client
+                    .get(self.sources.blaseball("database/allTeams")),
+            )
+            .await?;
+        Ok(decode("teams", &body).await?)
+    }
+
+    async fn players(&self) -> Result<Vec<Position>> {
+        debug!("Getting players");
+        self.chronicler().players().await
+    }
+
+    async fn feed_events(
+        &self,
+        kind: FeedEventType,
+        limit: Option<usize>,
+    ) -> Result<Vec<FeedEvent>> {
+        debug!("Getting {:?} feed events", kind);
+        let body = self
+            .fetcher
+            .fetch(
+                "feed",
+                self.fetcher
+                    .client
+                    .get(self.sources.blaseball("database/feed/global"))
+                    .query(&FeedQuery { kind, limit })
+                    .map_err(|x| x.into_inner())?,
+            )
+            .await?;
+        Ok(decode("feed", &body).await?)
+    }
+
+    async fn past_games(&self, season: isize) -> Result<Vec<GameUpdate>> {
+        debug!("Getting past games");
+        self.chronicler().games(season).await
+    }
+
+    async fn idols(&self) -> Result<Vec<Idol>> {
+        debug!("Getting idols");
+        let body = self
+            .fetcher
+            .fetch(
+                "idols",
+                self.fetcher
+                    .client
+                    .get(self.sources.blaseball("api/getIdols")),
+            )
+            .await?;
+        Ok(decode::<Idols>("idols", &body).await?.idols)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FixtureProvider {
+    pub strikeouts: Vec<StrikeoutLeader>,
+    pub at_bats: Vec<AtBatLeader>,
+    pub pitcher_stats: Vec<PitchingStats>,
+    pub batter_stats: Vec<BattingStats>,
+    pub teams: Vec<Team>,
+    pub players: Vec<Position>,
+    pub feed: HashMap<FeedEventType, Vec<FeedEvent>>,
+    pub past_games: Vec<GameUpdate>,
+    pub idols: Vec<Idol>,
+    pub missing: Vec<Dataset>,
+}
+
+impl FixtureProvider {
+    fn check(&self, dataset: Dataset) -> Result<()> {
+        ensure!(
+            !self.missing.contains(&dataset),
+            "{} missing from fixture",
+            dataset
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DataProvider for FixtureProvider {
+    async fn strikeout_leaders(&self, _season: isize) -> Result<Vec<StrikeoutLeader>> {
+        self.check(Dataset::Strikeouts)?;
+        Ok(self.strikeouts.clone())
+    }
+
+    async fn at_bat_leaders(&self, _season: isize) -> Result<Vec<AtBatLeader>> {
+        self.check(Dataset::AtBats)?;
+        Ok(self.at_bats.clone())
+    }
+
+    async fn pitcher_stats(
+        &self,
+        player_ids: &[PlayerId],
+        _season: isize,
+    ) -> Result<Vec<PitchingStats>> {
+        self.check(Dataset::PitcherStats)?;
+        Ok(self
+            .pitcher_stats
+            .iter()
+            .filter(|x| player_ids.contains(&x.player_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn batter_stats(
+        &self,
+        player_ids: &[PlayerId],
+        _season: isize,
+    ) -> Result<Vec<BattingStats>> {
+        self.check(Dataset::BatterStats)?;
+        Ok(self
+            .batter_stats
+            .iter()
+            .filter(|x| player_ids.contains(&x.player_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn teams(&self) -> Result<Vec<Team>> {
+        self.check(Dataset::Teams)?;
+        Ok(self.teams.clone())
+    }
+
+    async fn players(&self) -> Result<Vec<Position>> {
+        self.check(Dataset::Players)?;
+        Ok(self.players.clone())
+    }
+
+    async fn feed_events(
+        &self,
+        kind: FeedEventType,
+        limit: Option<usize>,
+    ) -> Result<Vec<FeedEvent>> {
+        self.check(Dataset::Feed(kind))?;
+        let items = self.feed.get(&kind).map(Vec::as_slice).unwrap_or_default();
+        Ok(items
+            .iter()
+            .take(limit.unwrap_or(items.len()))
+            .cloned()
+            .collect())
+    }
+
+    async fn past_games(&self, season: isize) -> Result<Vec<GameUpdate>> {
+        self.check(Dataset::PastGames)?;
+        Ok(self
+            .past_games
+            .iter()
+            .filter(|x| x.data.season == season)
+            .cloned()
+            .collect())
+    }
+
+    async fn idols(&self) -> Result<Vec<Idol>> {
+        self.check(Dataset::Idols)?;
+        Ok(self.idols.clone())
+    }
+}
@@ -0,0 +1,206 @@
+use super::dataset::Dataset;
+use super::models::{
+    AtBatLeader, BattingStats, Event, FeedEvent, FeedEventType, Game, GameUpdate, Idol,
+    PitchingStats, PlayerId, Position, StrikeoutLeader, Team,
+};
+use super::provider::{DataProvider, FixtureProvider};
+use super::{State, StateOptions};
+use anyhow::{ensure, Result};
+use async_trait::async_trait;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub season: isize,
+    pub games: Vec<Game>,
+    pub data: FixtureProvider,
+}
+
+fn is_compressed(path: &Path) -> bool {
+    path.extension().and_then(|x| x.to_str()) == Some("gz")
+}
+
+impl Snapshot {
+    pub async fn record(
+        games: Vec<Game>,
+        season: isize,
+        provider: &(impl DataProvider + ?Sized),
+        options: &StateOptions,
+    ) -> Result<(State, Self)> {
+        let recorder = Recorder::new(provider);
+        let state = State::from_games_and_season(games.clone(), season, &recorder, options).await?;
+        Ok((state, recorder.into_snapshot(games, season)))
+    }
+
+    pub async fn record_event(
+        data: &Event,
+        provider: &(impl DataProvider + ?Sized),
+        options: &StateOptions,
+    ) -> Result<(State, Self)> {
+        Self::record(
+            data.upcoming_games(),
+            data.value.games.sim.season,
+            provider,
+            options,
+        )
+        .await
+    }
+
+    pub async fn state(&self, options: &StateOptions) -> Result<State> {
+        Ok(
+            State::from_games_and_season(self.games.clone(), self.season, &self.data, options)
+                .await?,
+        )
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = BufReader::new(File::open(path)?);
+        let reader: Box<dyn Read> = if is_compressed(path) {
+            Box::new(GzDecoder::new(file))
+        } else {
+            Box::new(file)
+        };
+        let value: serde_json::Value = serde_json::from_reader(reader)?;
+        let version = value.get("version").and_then(|x| x.as_u64());
+        ensure!(
+            version == Some(SNAPSHOT_VERSION.into()),
+            "Unsupported snapshot version {} (expected {})",
+            version.map_or_else(|| "missing".to_string(), |x| x.to_string()),
+            SNAPSHOT_VERSION
+        );
+        Ok(serde_json::from_value(value)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let file = BufWriter::new(File::create(path)?);
+        if is_compressed(path) {
+            let mut encoder = GzEncoder::new(file, Compression::default());
+            serde_json::to_writer(&mut encoder, self)?;
+            encoder.finish()?.flush()?;
+        } else {
+            let mut file = file;
+            serde_json::to_writer(&mut file, self)?;
+            file.flush()?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Recorder<'a, P: ?Sized> {
+    inner: &'a P,
+    recorded: Mutex<FixtureProvider>,
+}
+
+impl<'a, P: DataProvider + ?Sized> Recorder<'a, P> {
+    pub fn new(inner: &'a P) -> Self {
+        Self {
+            inner,
+            recorded: Mutex::default(),
+        }
+    }
+
+    pub fn into_snapshot(self, games: Vec<Game>, season: isize) -> Snapshot {
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            season,
+            games,
+            data: self.recorded.into_inner().unwrap(),
+        }
+    }
+
+    fn record<T: Clone>(
+        &self,
+        dataset: Dataset,
+        res: &Result<T>,
+        func: impl FnOnce(&mut FixtureProvider, T),
+    ) {
+        let mut recorded = self.recorded.lock().unwrap();
+        match res {
+            Ok(x) => func(&mut recorded, x.clone()),
+            Err(_) => recorded.missing.push(dataset),
+        }
+    }
+}
+
+#[async_trait]
+impl<'a, P: DataProvider + ?Sized> DataProvider for Recorder<'a, P> {
+    async fn strikeout_leaders(&self, season: isize) -> Result<Vec<StrikeoutLeader>> {
+        let res = self.inner.strikeout_leaders(season).await;
+        self.record(Dataset::Strikeouts, &res, |x, y| x.strikeouts = y);
+        res
+    }
+
+    async fn at_bat_leaders(&self, season: isize) -> Result<Vec<AtBatLeader>> {
+        let res = self.inner.at_bat_leaders(season).await;
+        self.record(Dataset::AtBats, &res, |x, y| x.at_bats = y);
+        res
+    }
+
+    async fn pitcher_stats(
+        &self,
+        player_ids: &[PlayerId],
+        season: isize,
+    ) -> Result<Vec<PitchingStats>> {
+        let res = self.inner.pitcher_stats(player_ids, season).await;
+        self.record(Dataset::PitcherStats, &res, |x, y| {
+            x.pitcher_stats.extend(y)
+        });
+        res
+    }
+
+    async fn batter_stats(
+        &self,
+        player_ids: &[PlayerId],
+        season: isize,
+    ) -> Result<Vec<BattingStats>> {
+        let res = self.inner.batter_stats(player_ids, season).await;
+        self.record(Dataset::BatterStats, &res, |x, y| x.batter_stats.extend(y));
+        res
+    }
+
+    async fn teams(&self) -> Result<Vec<Team>> {
+        let res = self.inner.teams().await;
+        self.record(Dataset::Teams, &res, |x, y| x.teams = y);
+        res
+    }
+
+    async fn players(&self) -> Result<Vec<Position>> {
+        let res = self.inner.players().await;
+        self.record(Dataset::Players, &res, |x, y| x.players = y);
+        res
+    }
+
+    async fn feed_events(
+        &self,
+        kind: FeedEventType,
+        limit: Option<usize>,
+    ) -> Result<Vec<FeedEvent>> {
+        let res = self.inner.feed_events(kind, limit).await;
+        self.record(Dataset::Feed(kind), &res, |x, y| {
+            x.feed.entry(kind).or_default().extend(y)
+        });
+        res
+    }
+
+    async fn past_games(&self, season: isize) -> Result<Vec<GameUpdate>> {
+        let res = self.inner.past_games(season).await;
+        self.record(Dataset::PastGames, &res, |x, y| x.past_games.extend(y));
+        res
+    }
+
+    async fn idols(&self) -> Result<Vec<Idol>> {
+        let res = self.inner.idols().await;
+        self.record(Dataset::Idols, &res, |x, y| x.idols = y);
+        res
+    }
+}
@@ -1,185 +1,307 @@
+use super::dataset::Dataset;
+use super::error::StateError;
+use super::index::Index;
 use super::models::{
-    AtBatLeader, Event, FeedItem, Game, GameUpdate, GameUpdates, Idol, Idols, PitchingStats,
-    Position, StrikeoutLeader, Team,
+    AtBatLeader, BattingStats, Event, FeedEvent, FeedEventType, Game, GameUpdate, Idol,
+    PitchingStats, PlayerId, Position, StrikeoutLeader, Team, TeamId,
 };
-use anyhow::Result;
+use super::provider::DataProvider;
+use futures::future::join_all;
+use futures::join;
 use log::*;
-use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FetchMode {
+    Strict,
+    Degraded,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for FetchMode {
+    fn default() -> Self {
+        Self::Degraded
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FeedRequest {
+    pub kind: FeedEventType,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StateOptions {
+    pub mode: FetchMode,
+    pub feed: Vec<FeedRequest>,
+}
+
+impl Default for StateOptions {
+    fn default() -> Self {
+        Self {
+            mode: FetchMode::default(),
+            feed: vec![
+                FeedRequest {
+                    kind: FeedEventType::BlackHoleOutcome,
+                    limit: None,
+                },
+                FeedRequest {
+                    kind: FeedEventType::Sun2Outcome,
+                    limit: Some(100),
+                },
+            ],
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct State {
-    pub strikeouts: Vec<StrikeoutLeader>,
-    pub at_bats: Vec<AtBatLeader>,
-    pub pitcher_stats: Vec<PitchingStats>,
-    pub teams: Vec<Team>,
-    pub players: Vec<Position>,
-    pub games: Vec<Game>,
-    pub past_games: Vec<GameUpdate>,
-    pub idols: Vec<Idol>,
-    pub black_hole: Vec<FeedItem>,
-    pub sun_2: Vec<FeedItem>,
-    pub season: isize,
+    strikeouts: Vec<StrikeoutLeader>,
+    at_bats: Vec<AtBatLeader>,
+    pitcher_stats: Vec<PitchingStats>,
+    batter_stats: Vec<BattingStats>,
+    teams: Vec<Team>,
+    players: Vec<Position>,
+    games: Vec<Game>,
+    past_games: Vec<GameUpdate>,
+    idols: Vec<Idol>,
+    feed: Vec<FeedEvent>,
+    season: isize,
+    missing: Vec<Dataset>,
+    index: Index,
+}
+
+#[derive(Default)]
+struct Failures(Vec<(Dataset, anyhow::Error)>);
+
+impl Failures {
+    fn check<T: Default>(&mut self, dataset: Dataset, res: anyhow::Result<T>) -> T {
+        res.unwrap_or_else(|err| {
+            self.0.push((dataset, err));
+            T::default()
+        })
+    }
 }
 
 impl State {
-    pub async fn from_event(data: &Event) -> Result<Self> {
-        let games = if data.value.games.tomorrow_schedule.is_empty() {
-            warn!("No games scheduled, checking current games");
-            data.value.games.schedule.clone()
-        } else {
-            data.value.games.tomorrow_schedule.clone()
-        };
-        Self::from_games_and_season(games, data.value.games.sim.season).await
+    pub async fn from_event(
+        data: &Event,
+        provider: &(impl DataProvider + ?Sized),
+        options: &StateOptions,
+    ) -> Result<Self, StateError> {
+        Self::from_games_and_season(
+            data.upcoming_games(),
+            data.value.games.sim.season,
+            provider,
+            options,
+        )
+        .await
     }
 
-    pub async fn from_games_and_season(games: Vec<Game>, season: isize) -> Result<Self> {
-        #[derive(Deserialize)]
-        struct Positions {
-            data: Vec<Position>,
-        }
-        let client = surf::Client::new();
-        let comma_pitchers = games
+    pub async fn from_games_and_season(
+        games: Vec<Game>,
+        season: isize,
+        provider: &(impl DataProvider + ?Sized),
+        options: &StateOptions,
+    ) -> Result<Self, StateError> {
+        let pitcher_ids = games
             .iter()
             .filter_map(Game::pitcher_ids)
             .flatten()
-            .collect::<Vec<&str>>()
-            .join(",");
-        debug!("Getting batter strikeouts");
-
-        #[derive(Serialize)]
-        struct LeadersQuery {
-            category: &'static str,
-            stat: &'static str,
-            season: isize,
-        }
+            .collect::<Vec<PlayerId>>();
+        let teams_and_batters = async {
+            let teams = provider.teams().await;
+            let batter_ids = teams
+                .iter()
+                .flatten()
+                .filter(|x| games.iter().any(|y| y.team_ids().any(|z| z == x.id)))
+                .flat_map(|x| x.lineup.iter().copied())
+                .collect::<Vec<PlayerId>>();
+            let batter_stats = if batter_ids.is_empty() {
+                Ok(Vec::new())
+            } else {
+                provider.batter_stats(&batter_ids, season).await
+            };
+            (teams, batter_stats)
+        };
+        let (
+            strikeouts,
+            at_bats,
+            pitcher_stats,
+            (teams, batter_stats),
+            players,
+            feed,
+            past_games,
+            idols,
+        ) = join!(
+            provider.strikeout_leaders(season),
+            provider.at_bat_leaders(season),
+            provider.pitcher_stats(&pitcher_ids, season),
+            teams_and_batters,
+            provider.players(),
+            join_all(
+                options
+                    .feed
+                    .iter()
+                    .map(|x| provider.feed_events(x.kind, x.limit))
+            ),
+            provider.past_games(season),
+            provider.idols(),
+        );
 
-        #[derive(Serialize)]
-        #[serde(rename_all = "camelCase")]
-        struct StatsQuery {
-            category: &'static str,
-            player_ids: String,
-            season: isize,
-        }
+        let mut failures = Failures::default();
+        let strikeouts = failures.check(Dataset::Strikeouts, strikeouts);
+        let at_bats = failures.check(Dataset::AtBats, at_bats);
+        let pitcher_stats = failures.check(Dataset::PitcherStats, pitcher_stats);
+        let teams = failures.check(Dataset::Teams, teams);
+        let players = failures.check(Dataset::Players, players);
+        let batter_stats = failures.check(Dataset::BatterStats, batter_stats);
+        let feed = options
+            .feed
+            .iter()
+            .zip(feed)
+            .flat_map(|(x, y)| failures.check(Dataset::Feed(x.kind), y))
+            .collect();
+        let past_games = failures.check(Dataset::PastGames, past_games);
+        let idols = failures.check(Dataset::Idols, idols);
 
-        #[derive(Serialize)]
-        struct GamesQuery {
-            season: isize,
+        let Failures(failures) = failures;
+        let strict = options.mode == FetchMode::Strict;
+        if (strict && !failures.is_empty()) || failures.iter().any(|x| x.0.is_required()) {
+            return Err(StateError { failures });
+        }
+        for (dataset, err) in &failures {
+            warn!("Couldn't get {}, continuing without it: {}", dataset, err);
         }
 
-        let strikeouts: Vec<StrikeoutLeader> = client
-            .get("https://api.blaseball-reference.com/v1/seasonLeaders")
-            .query(&LeadersQuery {
-                category: "batting",
-                stat: "strikeouts",
-                season,
-            })
-            .map_err(|x| x.into_inner())?
-            .await
-            .map_err(|x| x.into_inner())?
-            .body_json()
-            .await
-            .unwrap_or_else(|_| Vec::new());
-        debug!("Getting at-bats");
-        let at_bats: Vec<AtBatLeader> = client
-            .get("https://api.blaseball-reference.com/v1/seasonLeaders")
-            .query(&LeadersQuery {
-                category: "batting",
-                stat: "at_bats",
-                season,
-            })
-            .map_err(|x| x.into_inner())?
-            .send()
-            .await
-            .map_err(|x| x.into_inner())?
-            .body_json()
-            .await
-            .unwrap_or_else(|_| Vec::new());
-        debug!("Getting pitcher stats");
-        let pitcher_stats: Vec<PitchingStats> = client
-            .get("https://api.blaseball-reference.com/v1/playerStats")
-            .query(&StatsQuery {
-                category: "pitching",
-                player_ids: comma_pitchers,
-                season,
-            })
-            .map_err(|x| x.into_inner())?
-            .send()
-            .await
-            .map_err(|x| x.into_inner())?
-            .body_json()
-            .await
-            .unwrap_or_else(|_| Vec::new());
-        debug!("Getting teams");
-        let teams: Vec<Team> = client
-            .get("https://www.blaseball.com/database/allTeams")
-            .send()
-            .await
-            .map_err(|x| x.into_inner())?
-            .body_json()
-            .await
-            .map_err(|x| x.into_inner())?;
-        debug!("Getting players");
-        let players = client
-            .get("https://api.sibr.dev/chronicler/v1/players?forbidden=false")
-            .send()
-            .await
-            .map_err(|x| x.into_inner())?
-            .body_json::<Positions>()
-            .await
-            .map_err(|x| x.into_inner())?
-            .data;
-        debug!("Getting Black Hole events");
-        let black_hole = client
-            .get("https://www.blaseball.com/database/feed/global?type=157")
-            .send()
-            .await
-            .map_err(|x| x.into_inner())?
-            .body_json::<Vec<FeedItem>>()
-            .await
-            .map_err(|x| x.into_inner())?;
-        debug!("Getting Sun 2 events");
-        let sun_2 = client
-            .get("https://www.blaseball.com/database/feed/global?type=156&limit=100")
-            .send()
-            .await
-            .map_err(|x| x.into_inner())?
-            .body_json::<Vec<FeedItem>>()
-            .await
-            .map_err(|x| x.into_inner())?;
-        debug!("Getting past games");
-        let past_games = client
-            .get("https://api.sibr.dev/chronicler/v1/games")
-            .query(&GamesQuery { season })
-            .map_err(|x| x.into_inner())?
-            .send()
-            .await
-            .map_err(|x| x.into_inner())?
-            .body_json::<GameUpdates>()
-            .await
-            .map_err(|x| x.into_inner())?
-            .data;
-        debug!("Getting idols");
-        let idols = client
-            .get("https://www.blaseball.com/api/getIdols")
-            .send()
-            .await
-            .map_err(|x| x.into_inner())?
-            .body_json::<Idols>()
-            .await
-            .map_err(|x| x.into_inner())?
-            .idols;
-        Ok(Self {
+        let mut state = Self {
             strikeouts,
             at_bats,
             pitcher_stats,
+            batter_stats,
             teams,
             players,
             games,
             past_games,
             idols,
-            black_hole,
-            sun_2,
+            feed,
             season,
-        })
+            missing: failures.into_iter().map(|x| x.0).collect(),
+            index: Index::default(),
+        };
+        state.reindex();
+        Ok(state)
+    }
+
+    fn reindex(&mut self) {
+        self.index = Index::build(self);
+    }
+
+    pub fn strikeouts(&self) -> &[StrikeoutLeader] {
+        &self.strikeouts
+    }
+
+    pub fn at_bats(&self) -> &[AtBatLeader] {
+        &self.at_bats
+    }
+
+    pub fn pitcher_stats(&self) -> &[PitchingStats] {
+        &self.pitcher_stats
+    }
+
+    pub fn batter_stats(&self) -> &[BattingStats] {
+        &self.batter_stats
+    }
+
+    pub fn teams(&self) -> &[Team] {
+        &self.teams
+    }
+
+    pub fn players(&self) -> &[Position] {
+        &self.players
+    }
+
+    pub fn games(&self) -> &[Game] {
+        &self.games
+    }
+
+    pub fn past_games(&self) -> &[GameUpdate] {
+        &self.past_games
+    }
+
+    pub fn idols(&self) -> &[Idol] {
+        &self.idols
+    }
+
+    pub fn feed(&self) -> &[FeedEvent] {
+        &self.feed
+    }
+
+    pub fn season(&self) -> isize {
+        self.season
+    }
+
+    pub fn missing(&self) -> &[Dataset] {
+        &self.missing
+    }
+
+    pub fn player(&self, id: PlayerId) -> Option<&Position> {
+        self.index.players.get(&id).map(|&i| &self.players[i])
+    }
+
+    pub fn team(&self, id: TeamId) -> Option<&Team> {
+        self.index.teams.get(&id).map(|&i| &self.teams[i])
+    }
+
+    pub fn pitching_stats(&self, player_id: PlayerId) -> Option<&PitchingStats> {
+        self.index
+            .pitcher_stats
+            .get(&player_id)
+            .map(|&i| &self.pitcher_stats[i])
+    }
+
+    pub fn batting_stats(&self, player_id: PlayerId) -> Option<&BattingStats> {
+        self.index
+            .batter_stats
+            .get(&player_id)
+            .map(|&i| &self.batter_stats[i])
+    }
+
+    pub fn strikeout_leader(&self, player_id: PlayerId) -> Option<&StrikeoutLeader> {
+        self.index
+            .strikeouts
+            .get(&player_id)
+            .map(|&i| &self.strikeouts[i])
+    }
+
+    pub fn at_bat_leader(&self, player_id: PlayerId) -> Option<&AtBatLeader> {
+        self.index
+            .at_bats
+            .get(&player_id)
+            .map(|&i| &self.at_bats[i])
+    }
+
+    pub fn past_game(&self, season: isize, day: isize, team_id: TeamId) -> Option<&Game> {
+        self.index
+            .past_games
+            .get(&(season, day, team_id))
+            .map(|&i| &self.past_games[i].data)
+    }
+
+    pub fn feed_events(&self, kind: FeedEventType) -> impl Iterator<Item = &FeedEvent> {
+        self.feed.iter().filter(move |x| x.is(kind))
+    }
+
+    pub fn season_feed_events(&self, kind: FeedEventType) -> impl Iterator<Item = &FeedEvent> {
+        let season = self.season;
+        self.feed_events(kind).filter(move |x| x.season == season)
+    }
+
+    pub fn player_feed_events(
+        &self,
+        player_id: PlayerId,
+        kind: FeedEventType,
+    ) -> impl Iterator<Item = &FeedEvent> {
+        self.feed_events(kind)
+            .filter(move |x| x.tags_player(player_id))
     }
 }
@@ -1,18 +1,54 @@
-use std::mem::take;
+use serde::{Deserialize, Serialize};
+use std::mem::{swap, take};
+use std::ops::{Index, IndexMut};
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TeamPosition {
     Home,
     Away,
 }
 
-#[derive(Debug, Copy, Clone)]
+impl TeamPosition {
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::Home => Self::Away,
+            Self::Away => Self::Home,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TeamPair<T> {
     pub home: T,
     pub away: T,
 }
 
 impl<T> TeamPair<T> {
+    pub fn from_fn<F>(mut func: F) -> Self
+    where
+        F: FnMut(TeamPosition) -> T,
+    {
+        TeamPair {
+            home: func(TeamPosition::Home),
+            away: func(TeamPosition::Away),
+        }
+    }
+
+    pub fn get(&self, pos: TeamPosition) -> &T {
+        match pos {
+            TeamPosition::Home => &self.home,
+            TeamPosition::Away => &self.away,
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: TeamPosition) -> &mut T {
+        match pos {
+            TeamPosition::Home => &mut self.home,
+            TeamPosition::Away => &mut self.away,
+        }
+    }
+
     pub fn map<M, F>(self, mut func: F) -> TeamPair<M>
     where
         F: FnMut(T) -> M,
@@ -72,6 +108,31 @@ impl<T> TeamPair<T> {
         func(self.home) || func(self.away)
     }
 
+    pub fn all<F>(self, mut func: F) -> bool
+    where
+        F: FnMut(T) -> bool,
+    {
+        func(self.home) && func(self.away)
+    }
+
+    pub fn position<F>(&self, mut func: F) -> Option<TeamPosition>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        if func(&self.home) {
+            Some(TeamPosition::Home)
+        } else if func(&self.away) {
+            Some(TeamPosition::Away)
+        } else {
+            None
+        }
+    }
+
+    pub fn swap(mut self) -> Self {
+        swap(&mut self.home, &mut self.away);
+        self
+    }
+
     pub fn as_ref(&self) -> TeamPair<&T> {
         TeamPair {
             home: &self.home,
@@ -92,6 +153,30 @@ impl<T> TeamPair<T> {
             away: (self.away, other.away),
         }
     }
+
+    pub fn zip_with<B, M, F>(self, other: TeamPair<B>, mut func: F) -> TeamPair<M>
+    where
+        F: FnMut(T, B) -> M,
+    {
+        TeamPair {
+            home: func(self.home, other.home),
+            away: func(self.away, other.away),
+        }
+    }
+}
+
+impl<T> Index<TeamPosition> for TeamPair<T> {
+    type Output = T;
+
+    fn index(&self, pos: TeamPosition) -> &T {
+        self.get(pos)
+    }
+}
+
+impl<T> IndexMut<TeamPosition> for TeamPair<T> {
+    fn index_mut(&mut self, pos: TeamPosition) -> &mut T {
+        self.get_mut(pos)
+    }
 }
 
 enum TeamPairPosition<T> {
@@ -100,6 +185,7 @@ enum TeamPairPosition<T> {
     End,
 }
 
+#[allow(clippy::derivable_impls)]
 impl<T> Default for TeamPairPosition<T> {
     fn default() -> Self {
         Self::End
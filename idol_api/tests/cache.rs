@@ -0,0 +1,86 @@
+use anyhow::Result;
+use idol_api::provider::{DataProvider, HttpProvider};
+use idol_api::ResponseCache;
+use idol_test_support::{Fixtures, MockServer};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const TEAMS: &str = "/blaseball/database/allTeams";
+
+fn cache_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("idol_cache_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[async_std::test]
+async fn serves_fresh_entries_without_requests() -> Result<()> {
+    let server = MockServer::start(Fixtures::sample()).await?;
+    let dir = cache_dir("fresh");
+    let provider = HttpProvider::new(server.sources()).with_cache(ResponseCache::new(&dir));
+
+    let first = provider.teams().await?;
+    let second = provider.teams().await?;
+    fs::remove_dir_all(&dir)?;
+    assert_eq!(first.len(), second.len());
+    assert_eq!(server.hits(TEAMS), vec![200]);
+    Ok(())
+}
+
+#[async_std::test]
+async fn revalidates_stale_entries() -> Result<()> {
+    let server = MockServer::start(Fixtures::sample()).await?;
+    let dir = cache_dir("stale");
+    let cache = ResponseCache::new(&dir).with_ttl("teams", Duration::from_secs(0));
+    let provider = HttpProvider::new(server.sources()).with_cache(cache);
+
+    let first = provider.teams().await?;
+    let second = provider.teams().await?;
+    fs::remove_dir_all(&dir)?;
+    assert_eq!(server.hits(TEAMS), vec![200, 304]);
+    assert_eq!(
+        serde_json::to_value(&first)?,
+        serde_json::to_value(&second)?
+    );
+    Ok(())
+}
+
+#[async_std::test]
+async fn refetches_without_a_cache() -> Result<()> {
+    let server = MockServer::start(Fixtures::sample()).await?;
+    let provider = HttpProvider::new(server.sources());
+
+    provider.teams().await?;
+    provider.teams().await?;
+    assert_eq!(server.hits(TEAMS), vec![200, 200]);
+    Ok(())
+}
+
+fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn reads_ttls_from_vars() {
+    let dir = cache_dir("vars").display().to_string();
+    let ttls = [
+        ("RESPONSE_CACHE_TTL", "60"),
+        ("RESPONSE_CACHE_TTL_PAST_GAMES", "3600"),
+        ("RESPONSE_CACHE_TTL_TEAMS", "soon"),
+    ];
+    assert!(ResponseCache::from_vars(vars(&ttls)).is_none());
+
+    let cache = ResponseCache::from_vars(
+        vars(&ttls)
+            .into_iter()
+            .chain(vars(&[("RESPONSE_CACHE", &dir)])),
+    )
+    .unwrap();
+    assert_eq!(cache.ttl("past_games"), Duration::from_secs(3600));
+    assert_eq!(cache.ttl("teams"), Duration::from_secs(60));
+    assert_eq!(cache.ttl("idols"), Duration::from_secs(60));
+}
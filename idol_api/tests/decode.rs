@@ -0,0 +1,79 @@
+use idol_api::decode;
+use idol_api::models::{Game, Player, Team, Weather};
+use idol_api::team_pair::TeamPair;
+use std::fs;
+
+#[async_std::test]
+async fn reports_failing_field_path() {
+    let body = br#"[{"id": "8d87c468-699a-47a8-b40d-cfb73a5660ad", "fullName": "Baltimore Crabs", "lineup": [42]}]"#;
+    let err = decode::<Vec<Team>>("teams", body).await.unwrap_err();
+    assert_eq!(err.path, "[0].lineup[0]");
+    let saved = err.saved.expect("payload wasn't saved");
+    assert_eq!(fs::read(&saved).unwrap(), body);
+    let again = decode::<Vec<Team>>("teams", body).await.unwrap_err();
+    assert_eq!(again.saved.as_ref(), Some(&saved));
+    fs::remove_file(saved).unwrap();
+}
+
+#[async_std::test]
+async fn tolerates_unknown_and_missing_fields() {
+    let body = br#"[{"id": "8d87c468-699a-47a8-b40d-cfb73a5660ad", "fullName": "Baltimore Crabs", "shameRuns": 0}]"#;
+    let teams = decode::<Vec<Team>>("teams", body).await.unwrap();
+    assert!(teams[0].lineup.is_empty());
+}
+
+#[async_std::test]
+async fn leaves_missing_attributes_unset() {
+    let body = br#"[{"id": "00000000-0000-0000-0000-000000000001", "name": "Wyatt Best", "ruthlessness": 0.8, "patheticism": 0.3, "thwackability": 0.5}]"#;
+    let players = decode::<Vec<Player>>("players", body).await.unwrap();
+    assert_eq!(players[0].thwackability, Some(0.5));
+    assert_eq!(players[0].moxie, None);
+    assert_eq!(players[0].pitching_rating, None);
+
+    let body = br#"[{"id": "00000000-0000-0000-0000-000000000001", "name": "Wyatt Best", "patheticism": 0.3}]"#;
+    let err = decode::<Vec<Player>>("players", body).await.unwrap_err();
+    assert_eq!(err.path, "[0]");
+    if let Some(saved) = err.saved {
+        fs::remove_file(saved).unwrap();
+    }
+}
+
+#[async_std::test]
+async fn decodes_game_conditions() {
+    let body = br#"[
+        {"id": "00000000-0000-0000-0000-0000000000a1", "awayTeam": "8d87c468-699a-47a8-b40d-cfb73a5660ad", "awayTeamName": "Baltimore Crabs", "homeTeam": "c73b705c-40ad-4633-a6ed-d357ee2e2bcf", "homeTeamName": "Tokyo Lift", "awayOdds": 0.55, "homeOdds": 0.45, "day": 98, "season": 11, "weather": 7, "stadiumId": "stadium", "seriesIndex": 2, "seriesLength": 5, "isPostseason": true, "isTitleMatch": true, "homeStrikes": 3, "awayStrikes": 4, "homeBalls": 4, "awayBalls": 4, "homeBases": 4, "awayBases": 5, "homeOuts": 3, "awayOuts": 3, "outcomes": ["Reverb"]},
+        {"id": "00000000-0000-0000-0000-0000000000a2", "awayTeam": "8d87c468-699a-47a8-b40d-cfb73a5660ad", "awayTeamName": "Baltimore Crabs", "homeTeam": "c73b705c-40ad-4633-a6ed-d357ee2e2bcf", "homeTeamName": "Tokyo Lift", "awayOdds": 0.55, "homeOdds": 0.45, "day": 98, "season": 11, "weather": 99},
+        {"id": "00000000-0000-0000-0000-0000000000a3", "awayTeam": "8d87c468-699a-47a8-b40d-cfb73a5660ad", "awayTeamName": "Baltimore Crabs", "homeTeam": "c73b705c-40ad-4633-a6ed-d357ee2e2bcf", "homeTeamName": "Tokyo Lift", "awayOdds": 0.55, "homeOdds": 0.45, "day": 98, "season": 11}
+    ]"#;
+    let games = decode::<Vec<Game>>("games", body).await.unwrap();
+
+    let game = &games[0];
+    assert_eq!(game.weather, Some(Weather::SolarEclipse));
+    assert!(game.weather.unwrap().can_replace_pitcher());
+    assert_eq!(game.stadium_id.as_deref(), Some("stadium"));
+    assert_eq!((game.series_index, game.series_length), (2, 5));
+    assert!(game.is_postseason && game.is_special());
+    assert_eq!(
+        game.strikes(),
+        TeamPair {
+            home: Some(3),
+            away: Some(4)
+        }
+    );
+    assert_eq!((game.home_balls, game.away_balls), (Some(4), Some(4)));
+    assert_eq!((game.home_bases, game.away_bases), (Some(4), Some(5)));
+    assert_eq!(game.outs().home, Some(3));
+    assert_eq!(game.outcomes, vec!["Reverb"]);
+
+    let weather = games[1].weather.unwrap();
+    assert_eq!(weather, Weather::Unknown(99));
+    assert!(!weather.is_known());
+    assert_eq!(isize::from(weather), 99);
+
+    let game = &games[2];
+    assert_eq!(game.weather, None);
+    assert_eq!(game.stadium_id, None);
+    assert!(!game.is_postseason && !game.is_special());
+    assert_eq!(game.strikes(), TeamPair::default());
+    assert!(game.outcomes.is_empty());
+}
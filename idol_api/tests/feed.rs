@@ -0,0 +1,154 @@
+use anyhow::Result;
+use idol_api::models::{FeedEvent, FeedEventType, GameUpdate, PlayerId, TeamId};
+use idol_api::{FeedRequest, State, StateOptions};
+use idol_test_support::Fixtures;
+use std::collections::HashSet;
+
+fn event(
+    kind: FeedEventType,
+    season: isize,
+    day: isize,
+    player_tags: Vec<PlayerId>,
+    team_tags: Vec<TeamId>,
+) -> FeedEvent {
+    FeedEvent {
+        kind,
+        player_tags,
+        team_tags,
+        game_tags: Vec::new(),
+        description: String::new(),
+        metadata: serde_json::Value::Null,
+        day,
+        season,
+    }
+}
+
+fn options(kinds: &[FeedEventType]) -> StateOptions {
+    StateOptions {
+        feed: kinds
+            .iter()
+            .map(|&kind| FeedRequest { kind, limit: None })
+            .collect(),
+        ..StateOptions::default()
+    }
+}
+
+#[test]
+fn round_trips_feed_event_types() {
+    let mut known = 0;
+    for i in -1..=200 {
+        let kind = FeedEventType::from(i);
+        assert_eq!(isize::from(kind), i);
+        if !matches!(kind, FeedEventType::Unknown(_)) {
+            known += 1;
+        }
+    }
+    assert_eq!(known, 36);
+    assert_eq!(FeedEventType::from(54), FeedEventType::Incineration);
+    assert_eq!(FeedEventType::from(156), FeedEventType::Sun2Outcome);
+    assert_eq!(FeedEventType::from(16), FeedEventType::Unknown(16));
+    assert_eq!(FeedEventType::Unknown(156), FeedEventType::Sun2Outcome);
+    let kinds = [FeedEventType::Unknown(156), FeedEventType::Sun2Outcome]
+        .iter()
+        .copied()
+        .collect::<HashSet<_>>();
+    assert_eq!(kinds.len(), 1);
+
+    let event: FeedEvent =
+        serde_json::from_str(r#"{"type": 157, "day": 3, "season": 11}"#).unwrap();
+    assert!(event.is(FeedEventType::BlackHoleOutcome));
+    assert!(event.team_tags.is_empty());
+    let event: FeedEvent =
+        serde_json::from_str(r#"{"type": 999, "day": 3, "season": 11}"#).unwrap();
+    assert_eq!(event.kind, FeedEventType::Unknown(999));
+}
+
+#[async_std::test]
+async fn queries_feed_events() -> Result<()> {
+    let mut fixtures = Fixtures::sample();
+    let pitcher = PlayerId::from_u128(1);
+    let other = PlayerId::from_u128(2);
+    fixtures.data.feed.insert(
+        FeedEventType::Incineration,
+        vec![
+            event(FeedEventType::Incineration, 10, 40, vec![other], vec![]),
+            event(FeedEventType::Incineration, 11, 2, vec![other], vec![]),
+            event(FeedEventType::Incineration, 11, 5, vec![pitcher], vec![]),
+        ],
+    );
+    fixtures.data.feed.insert(
+        FeedEventType::BirdsUnshell,
+        vec![
+            event(FeedEventType::BirdsUnshell, 10, 12, vec![pitcher], vec![]),
+            event(FeedEventType::BirdsUnshell, 11, 3, vec![other], vec![]),
+            event(
+                FeedEventType::BirdsUnshell,
+                11,
+                4,
+                vec![pitcher, other],
+                vec![],
+            ),
+        ],
+    );
+    let kinds = [FeedEventType::Incineration, FeedEventType::BirdsUnshell];
+    let state = State::from_event(&fixtures.events[0], &fixtures.data, &options(&kinds)).await?;
+
+    let incinerations = state
+        .season_feed_events(FeedEventType::Incineration)
+        .map(|x| x.day)
+        .collect::<Vec<_>>();
+    assert_eq!(incinerations, vec![2, 5]);
+
+    let unshellings = state
+        .player_feed_events(pitcher, FeedEventType::BirdsUnshell)
+        .map(|x| (x.season, x.day))
+        .collect::<Vec<_>>();
+    assert_eq!(unshellings, vec![(10, 12), (11, 4)]);
+
+    assert_eq!(
+        state
+            .player_feed_events(pitcher, FeedEventType::Incineration)
+            .count(),
+        1
+    );
+    assert_eq!(state.feed_events(FeedEventType::Sun2Outcome).count(), 0);
+    Ok(())
+}
+
+#[async_std::test]
+async fn finds_games_of_feed_events() -> Result<()> {
+    let mut fixtures = Fixtures::sample();
+    let game = fixtures.events[0].value.games.tomorrow_schedule[0].clone();
+    let (away, home) = (game.away_team, game.home_team);
+    let mut past = game.clone();
+    past.day = 5;
+    fixtures.data.past_games.push(GameUpdate { data: past });
+    let kind = FeedEventType::Sun2Outcome;
+    fixtures.data.feed.insert(
+        kind,
+        vec![
+            event(kind, 11, 5, vec![], vec![home]),
+            event(kind, 11, 5, vec![], vec![away, home]),
+            event(kind, 11, 5, vec![], vec![]),
+            event(kind, 11, 6, vec![], vec![home]),
+            event(kind, 11, 5, vec![], vec![TeamId::from_u128(0x99)]),
+        ],
+    );
+    let state = State::from_event(&fixtures.events[0], &fixtures.data, &options(&[kind])).await?;
+
+    let games = state
+        .feed_events(kind)
+        .map(|x| x.game(&state).map(|x| (x.day, x.away_team, x.home_team)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        games,
+        vec![
+            Some((5, away, home)),
+            Some((5, away, home)),
+            None,
+            None,
+            None
+        ]
+    );
+    Ok(())
+}
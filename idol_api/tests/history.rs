@@ -0,0 +1,160 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use idol_api::chronicler::Version;
+use idol_api::history::{History, Timeline};
+use idol_api::models::{FeedEventType, GameId, GameUpdate, Phase, Simulation};
+use idol_api::{Dataset, State, StateOptions};
+use idol_test_support::Fixtures;
+use uuid::Uuid;
+
+fn start() -> DateTime<Utc> {
+    Utc.ymd(2021, 3, 1).and_hms(16, 0, 0)
+}
+
+fn version<T>(
+    entity_id: Uuid,
+    valid_from: DateTime<Utc>,
+    valid_to: Option<DateTime<Utc>>,
+    data: T,
+) -> Version<T> {
+    Version {
+        entity_id,
+        hash: String::new(),
+        valid_from,
+        valid_to,
+        data,
+    }
+}
+
+fn sim(day: isize, hours: i64) -> Version<Simulation> {
+    let valid_from = start() + Duration::hours(hours);
+    version(
+        Uuid::nil(),
+        valid_from,
+        Some(valid_from + Duration::hours(1)),
+        Simulation {
+            season: 11,
+            day,
+            phase: Phase::from(2),
+        },
+    )
+}
+
+#[test]
+fn finds_versions_at_boundaries() {
+    let t = start();
+    let timeline = Timeline::new(vec![
+        version(Uuid::nil(), t + Duration::hours(2), None, "second"),
+        version(Uuid::nil(), t, Some(t + Duration::hours(1)), "first"),
+    ]);
+    assert_eq!(timeline.at(t - Duration::seconds(1)), None);
+    assert_eq!(timeline.at(t), Some(&"first"));
+    assert_eq!(timeline.at(t + Duration::minutes(59)), Some(&"first"));
+    assert_eq!(timeline.at(t + Duration::hours(1)), None);
+    assert_eq!(timeline.at(t + Duration::hours(2)), Some(&"second"));
+    assert_eq!(timeline.at(t + Duration::days(30)), Some(&"second"));
+    assert_eq!(Timeline::<()>::default().at(t), None);
+}
+
+#[test]
+fn finds_time_of_day() {
+    let history = History::new(
+        Vec::new(),
+        Vec::new(),
+        vec![sim(1, 1), sim(0, 0), sim(3, 3)],
+    );
+    assert_eq!(history.time_of(11, 0), Some(start()));
+    assert_eq!(history.time_of(11, 1), Some(start() + Duration::hours(1)));
+    assert_eq!(history.time_of(11, 3), Some(start() + Duration::hours(3)));
+    assert_eq!(history.time_of(11, 2), None);
+    assert_eq!(history.time_of(11, 4), None);
+    assert_eq!(history.time_of(10, 99), None);
+}
+
+#[async_std::test]
+async fn reports_unversioned_datasets_missing() -> Result<()> {
+    let fixtures = Fixtures::sample();
+    let data = &fixtures.data;
+    let t = start();
+    let history = History::new(
+        data.players
+            .iter()
+            .map(|x| version(x.id.0, t, None, x.data.clone()))
+            .collect(),
+        data.teams
+            .iter()
+            .map(|x| version(x.id.0, t, None, x.clone()))
+            .collect(),
+        vec![sim(9, 0)],
+    );
+    let games = fixtures.events[0].upcoming_games();
+    let options = StateOptions::default();
+
+    let state = history
+        .state_at(t, games.clone(), 11, data, &options)
+        .await?;
+    assert_eq!(state.players().len(), 6);
+    assert_eq!(
+        state.missing(),
+        [
+            Dataset::Strikeouts,
+            Dataset::AtBats,
+            Dataset::PitcherStats,
+            Dataset::BatterStats,
+            Dataset::Feed(FeedEventType::BlackHoleOutcome),
+            Dataset::Feed(FeedEventType::Sun2Outcome),
+            Dataset::Idols,
+        ]
+    );
+
+    let provider = history.provider_at(t, data).with_stats();
+    let state = State::from_games_and_season(games, 11, &provider, &options).await?;
+    assert!(state.missing().is_empty());
+    Ok(())
+}
+
+#[async_std::test]
+async fn reconstructs_state_on_day() -> Result<()> {
+    let mut fixtures = Fixtures::sample();
+    let game = fixtures.events[0].upcoming_games()[0].clone();
+    for day in 7..10 {
+        let mut game = game.clone();
+        game.id = GameId::from_u128(day as u128);
+        game.day = day;
+        fixtures.data.past_games.push(GameUpdate { data: game });
+    }
+    let data = &fixtures.data;
+    let t = start();
+    let history = History::new(
+        data.players
+            .iter()
+            .map(|x| version(x.id.0, t, None, x.data.clone()))
+            .collect(),
+        data.teams
+            .iter()
+            .map(|x| version(x.id.0, t, None, x.clone()))
+            .collect(),
+        vec![sim(8, 0), sim(9, 1)],
+    );
+    let options = StateOptions::default();
+
+    let state = history.state_on(11, 8, data, &options).await?;
+    assert_eq!(state.season(), 11);
+    assert_eq!(
+        state.games().iter().map(|x| x.day).collect::<Vec<_>>(),
+        vec![8]
+    );
+    assert_eq!(
+        state
+            .past_games()
+            .iter()
+            .map(|x| x.data.day)
+            .collect::<Vec<_>>(),
+        vec![7]
+    );
+    assert_eq!(state.players().len(), 6);
+
+    let err = history.state_on(11, 10, data, &options).await.unwrap_err();
+    assert_eq!(err.to_string(), "No sim version for season 11 day 10");
+    Ok(())
+}
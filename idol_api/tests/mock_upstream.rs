@@ -0,0 +1,124 @@
+use anyhow::Result;
+use idol_api::chronicler::{Chronicler, EntityType, VersionQuery};
+use idol_api::models::{GameId, GameUpdate, PlayerId};
+use idol_api::provider::HttpProvider;
+use idol_api::{Dataset, FetchMode, State, StateOptions};
+use idol_test_support::{Fixtures, MockServer};
+use serde_json::json;
+
+#[async_std::test]
+async fn builds_state_from_upstream() -> Result<()> {
+    let fixtures = Fixtures::sample();
+    let event = fixtures.events[0].clone();
+    let server = MockServer::start(fixtures).await?;
+    let provider = HttpProvider::new(server.sources());
+
+    let state = State::from_event(&event, &provider, &StateOptions::default()).await?;
+    assert!(state.missing().is_empty());
+    assert_eq!(state.teams().len(), 2);
+    assert_eq!(state.players().len(), 6);
+    assert_eq!(state.games().len(), 1);
+    assert_eq!(state.idols().len(), 2);
+
+    let pitcher = PlayerId::from_u128(1);
+    assert_eq!(state.player(pitcher).unwrap().data.name, "Wyatt Best");
+    let stats = state.pitching_stats(pitcher).unwrap();
+    assert_eq!(stats.strikeouts_per_9, 9.5);
+    assert_eq!(stats.strikeouts_per_walk, Some(4.75));
+    let batter = state.batting_stats(PlayerId::from_u128(0x11)).unwrap();
+    assert_eq!(batter.on_base_percentage, Some(0.341));
+    Ok(())
+}
+
+#[async_std::test]
+async fn reports_missing_datasets() -> Result<()> {
+    let mut fixtures = Fixtures::sample();
+    fixtures.data.missing.push(Dataset::Idols);
+    let event = fixtures.events[0].clone();
+    let server = MockServer::start(fixtures).await?;
+    let provider = HttpProvider::new(server.sources());
+
+    let state = State::from_event(&event, &provider, &StateOptions::default()).await?;
+    assert_eq!(state.missing(), vec![Dataset::Idols]);
+
+    let strict = StateOptions {
+        mode: FetchMode::Strict,
+        ..StateOptions::default()
+    };
+    let err = State::from_event(&event, &provider, &strict)
+        .await
+        .unwrap_err();
+    assert_eq!(err.datasets().collect::<Vec<_>>(), vec![Dataset::Idols]);
+    Ok(())
+}
+
+#[async_std::test]
+async fn follows_chronicler_pages() -> Result<()> {
+    let mut fixtures = Fixtures::sample();
+    let versions = (0..5)
+        .map(|i| {
+            json!({
+                "entityId": format!("00000000-0000-0000-0000-00000000000{}", i),
+                "validFrom": "2020-10-05T16:00:00Z",
+                "validTo": null,
+                "data": { "index": i },
+            })
+        })
+        .collect();
+    fixtures.versions.insert("team".to_string(), versions);
+    let server = MockServer::start(fixtures).await?;
+    let chronicler = Chronicler::new(server.sources());
+
+    let versions = chronicler
+        .versions::<serde_json::Value>(
+            EntityType::Team,
+            &VersionQuery {
+                count: Some(2),
+                ..VersionQuery::default()
+            },
+        )
+        .await?;
+    let indexes = versions
+        .iter()
+        .map(|x| x.data["index"].as_u64().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(indexes, vec![0, 1, 2, 3, 4]);
+    Ok(())
+}
+
+#[async_std::test]
+async fn clamps_zero_concurrency() -> Result<()> {
+    let fixtures = Fixtures::sample();
+    let event = fixtures.events[0].clone();
+    let server = MockServer::start(fixtures).await?;
+    let provider = HttpProvider::new(server.sources()).with_concurrency(0);
+
+    let state = State::from_event(&event, &provider, &StateOptions::default()).await?;
+    assert!(state.missing().is_empty());
+    Ok(())
+}
+
+#[async_std::test]
+async fn pages_chronicler_players_and_games() -> Result<()> {
+    let mut fixtures = Fixtures::sample();
+    let game = fixtures.events[0].value.games.tomorrow_schedule[0].clone();
+    for (season, day) in [(10, 98), (11, 0), (11, 1), (11, 2), (11, 3), (11, 4)].iter() {
+        let mut game = game.clone();
+        game.id = GameId::from_u128((season * 1000 + day) as u128);
+        game.season = *season;
+        game.day = *day;
+        fixtures.data.past_games.push(GameUpdate { data: game });
+    }
+    fixtures.forbidden.push(PlayerId::from_u128(0x12));
+    let server = MockServer::start(fixtures).await?;
+    let chronicler = Chronicler::new(server.sources());
+
+    let players = chronicler.players().await?;
+    assert_eq!(players.len(), 5);
+    assert!(players.iter().all(|x| x.id != PlayerId::from_u128(0x12)));
+
+    let games = chronicler.games(11).await?;
+    let days = games.iter().map(|x| x.data.day).collect::<Vec<_>>();
+    assert_eq!(days, vec![0, 1, 2, 3, 4]);
+    Ok(())
+}
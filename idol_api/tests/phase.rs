@@ -0,0 +1,77 @@
+use idol_api::models::{Games, Phase};
+use idol_test_support::Fixtures;
+
+fn games(phase: Phase) -> Games {
+    let mut games = Fixtures::sample().events[0].value.games.clone();
+    games.sim.phase = phase;
+    games
+}
+
+#[test]
+fn round_trips_phases() {
+    for i in 0..=12 {
+        let phase = Phase::from(i);
+        assert!(!matches!(phase, Phase::Unknown(_)));
+        assert_eq!(phase, Phase::Unknown(i));
+        assert_eq!(isize::from(phase), i);
+    }
+    assert_eq!(Phase::from(4), Phase::Midseason);
+    assert_eq!(Phase::from(13), Phase::Unknown(13));
+    assert_eq!(Phase::from(-1), Phase::Unknown(-1));
+    assert_eq!(isize::from(Phase::Unknown(13)), 13);
+
+    assert_eq!(
+        serde_json::from_str::<Phase>("11").unwrap(),
+        Phase::Postseasonend
+    );
+    assert_eq!(
+        serde_json::from_str::<Phase>("42").unwrap(),
+        Phase::Unknown(42)
+    );
+    assert_eq!(serde_json::to_string(&Phase::Unknown(42)).unwrap(), "42");
+}
+
+#[test]
+fn classifies_phases() {
+    let regular = [Phase::Earlseason, Phase::Midseason, Phase::Lateseason];
+    let postseason = [
+        Phase::Earlpostseason,
+        Phase::Latepostseason,
+        Phase::Postseasonend,
+    ];
+    for i in -1..=13 {
+        let phase = Phase::from(i);
+        assert_eq!(
+            phase.is_regular_season(),
+            regular.contains(&phase),
+            "{:?}",
+            phase
+        );
+        assert_eq!(
+            phase.is_postseason(),
+            postseason.contains(&phase),
+            "{:?}",
+            phase
+        );
+    }
+}
+
+#[test]
+fn opens_betting() {
+    assert!(games(Phase::Midseason).betting_open());
+    assert!(!games(Phase::Earlsiesta).betting_open());
+    assert!(!games(Phase::Election).betting_open());
+    assert!(!games(Phase::Unknown(13)).betting_open());
+
+    let mut regular = games(Phase::Lateseason);
+    regular.tomorrow_schedule.clear();
+    assert!(regular.betting_open());
+
+    let postseason = games(Phase::Earlpostseason);
+    assert!(!postseason.tomorrow_schedule.is_empty());
+    assert!(postseason.betting_open());
+
+    let mut finished = games(Phase::Latepostseason);
+    finished.tomorrow_schedule.clear();
+    assert!(!finished.betting_open());
+}
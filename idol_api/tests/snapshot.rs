@@ -0,0 +1,45 @@
+use anyhow::Result;
+use idol_api::snapshot::{Snapshot, SNAPSHOT_VERSION};
+use idol_api::StateOptions;
+use idol_test_support::Fixtures;
+use std::fs;
+
+#[async_std::test]
+async fn round_trips_snapshots() -> Result<()> {
+    let fixtures = Fixtures::sample();
+    let (_, snapshot) = Snapshot::record_event(
+        &fixtures.events[0],
+        &fixtures.data,
+        &StateOptions::default(),
+    )
+    .await?;
+    assert_eq!(snapshot.version, SNAPSHOT_VERSION);
+
+    let path = std::env::temp_dir().join(format!("idol_snapshot_{}.json.gz", std::process::id()));
+    snapshot.save(&path)?;
+    let loaded = Snapshot::load(&path)?;
+    fs::remove_file(&path)?;
+    let state = loaded.state(&StateOptions::default()).await?;
+    assert!(state.missing().is_empty());
+    assert_eq!(state.players().len(), 6);
+    Ok(())
+}
+
+#[test]
+fn rejects_old_snapshots() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("idol_old_snapshot_{}.json", std::process::id()));
+    fs::write(
+        &path,
+        r#"{"version": 1, "season": 11, "games": [], "data": {"feed": [{"id": "x"}]}}"#,
+    )?;
+    let err = Snapshot::load(&path).unwrap_err();
+    fs::remove_file(&path)?;
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Unsupported snapshot version 1 (expected {})",
+            SNAPSHOT_VERSION
+        )
+    );
+    Ok(())
+}
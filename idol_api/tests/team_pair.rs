@@ -0,0 +1,37 @@
+use idol_api::team_pair::{TeamPair, TeamPosition};
+
+#[test]
+fn indexes_and_combines() {
+    let mut pair = TeamPair::from_fn(|pos| match pos {
+        TeamPosition::Home => 1,
+        TeamPosition::Away => 2,
+    });
+    assert_eq!(pair[TeamPosition::Away], 2);
+    pair[TeamPosition::Home] = 3;
+    assert_eq!(pair.position(|&x| x == 3), Some(TeamPosition::Home));
+    assert_eq!(pair.swap(), TeamPair { home: 2, away: 3 });
+    assert_eq!(
+        pair.zip_with(TeamPair { home: 10, away: 20 }, |x, y| x + y),
+        TeamPair { home: 13, away: 22 }
+    );
+    assert!(pair.all(|x| x > 1));
+    assert_eq!(TeamPosition::Home.opposite(), TeamPosition::Away);
+}
+
+#[test]
+fn round_trips_through_json() {
+    let pair = TeamPair {
+        home: "Crabs".to_string(),
+        away: "Lift".to_string(),
+    };
+    let json = serde_json::to_string(&pair).unwrap();
+    assert_eq!(json, r#"{"home":"Crabs","away":"Lift"}"#);
+    assert_eq!(
+        serde_json::from_str::<TeamPair<String>>(&json).unwrap(),
+        pair
+    );
+    assert_eq!(
+        serde_json::to_string(&TeamPosition::Away).unwrap(),
+        r#""away""#
+    );
+}
@@ -0,0 +1,34 @@
+CREATE TABLE algorithms_backup(
+    algorithm TEXT NOT NULL,
+    joke BOOLEAN NOT NULL,
+    webhook_id INTEGER NOT NULL
+);
+INSERT INTO algorithms_backup SELECT
+    CASE algorithm
+        WHEN 0 THEN 'so9'
+        WHEN 1 THEN 'ruthlessness'
+        WHEN 2 THEN 'stat_ratio'
+        WHEN 3 THEN 'lift'
+        WHEN 4 THEN 'bestness'
+        WHEN 5 THEN 'best_best'
+        WHEN 6 THEN 'worst_stat_ratio'
+        WHEN 7 THEN 'idols'
+        WHEN 8 THEN 'batting_stars'
+        WHEN 9 THEN 'name_length'
+        WHEN 10 THEN 'games_per_game'
+        WHEN 11 THEN 'games_name_per_game'
+        WHEN 12 THEN 'batting_multiplier'
+        WHEN 13 THEN 'hits'
+        WHEN 14 THEN 'home_runs'
+        WHEN 15 THEN 'steals'
+        ELSE 'unknown_' || algorithm
+    END, joke, webhook_id
+FROM algorithms;
+DROP TABLE algorithms;
+CREATE TABLE algorithms(
+    algorithm TEXT NOT NULL,
+    joke BOOLEAN NOT NULL,
+    webhook_id INTEGER NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE
+);
+INSERT INTO algorithms SELECT algorithm, joke, webhook_id FROM algorithms_backup;
+DROP TABLE algorithms_backup;
@@ -0,0 +1,50 @@
+use anyhow::{anyhow, Result};
+use idol_api::snapshot::Snapshot;
+use idol_api::StateOptions;
+use idol_bot::{best_from_state, load_registry, skip_missing};
+use std::env;
+
+#[async_std::main]
+async fn main() -> Result<()> {
+    let (flags, args): (Vec<String>, Vec<String>) =
+        env::args().skip(1).partition(|x| x.starts_with("--"));
+    let json = flags.iter().any(|x| x == "--json");
+    let path = args
+        .first()
+        .ok_or_else(|| anyhow!("Snapshot path missing!"))?;
+    let top = args.get(1).map(|x| x.parse()).transpose()?;
+    let snapshot = Snapshot::load(path)?;
+    let state = snapshot.state(&StateOptions::default()).await?;
+    let registry = load_registry()?;
+    if json {
+        let mut recommendations = Vec::new();
+        for algorithm in registry.iter().filter(|x| !skip_missing(*x, &state)) {
+            match algorithm.top(&state, top.unwrap_or(1)) {
+                Ok(ranked) => recommendations.extend(
+                    ranked
+                        .iter()
+                        .enumerate()
+                        .map(|(i, x)| x.recommendation(algorithm.metadata(), i + 1)),
+                ),
+                Err(err) => eprintln!("Algorithm failed: {}", err),
+            }
+        }
+        println!("{}", serde_json::to_string_pretty(&recommendations)?);
+    } else if let Some(count) = top {
+        for algorithm in registry.iter().filter(|x| !skip_missing(*x, &state)) {
+            let mut text = String::new();
+            match algorithm.write_top_to(&state, count, &mut text) {
+                Ok(_) => println!("{}", text),
+                Err(err) => eprintln!("Algorithm failed: {}", err),
+            }
+        }
+    } else {
+        for best in best_from_state(&registry, &state)
+            .into_iter()
+            .filter_map(|x| x.1)
+        {
+            print!("{}", best);
+        }
+    }
+    Ok(())
+}
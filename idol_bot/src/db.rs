@@ -1,6 +1,6 @@
 use anyhow::Result;
 use futures::prelude::*;
-use idol_predictor::algorithms::{ALGORITHMS, JOKE_ALGORITHMS};
+use idol_predictor::{Category, Registry};
 use log::*;
 use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
 use std::collections::BTreeSet;
@@ -16,7 +16,7 @@ pub struct Webhook {
 }
 
 pub struct AlgorithmRef {
-    pub algorithm: i64,
+    pub algorithm: String,
     pub joke: bool,
 }
 
@@ -40,7 +40,7 @@ impl Database {
         &self,
         webhook: &Webhook,
         joke: bool,
-    ) -> Result<Option<BTreeSet<i64>>> {
+    ) -> Result<Option<BTreeSet<String>>> {
         let db_algs = sqlx::query_scalar!(
             "SELECT algorithm FROM algorithms WHERE webhook_id = ? AND joke = ?",
             webhook.id,
@@ -48,7 +48,7 @@ impl Database {
         )
         .fetch(&self.pool)
         .err_into::<anyhow::Error>()
-        .try_collect::<BTreeSet<i64>>()
+        .try_collect::<BTreeSet<String>>()
         .await?;
 
         if db_algs.is_empty() {
@@ -58,22 +58,42 @@ impl Database {
         }
     }
 
-    pub async fn algorithms(&self, webhook: &Webhook, joke: bool) -> Result<BTreeSet<i64>> {
-        if let Some(algs) = self.try_algorithms(webhook, joke).await? {
-            Ok(algs)
+    pub async fn algorithms(
+        &self,
+        registry: &Registry,
+        webhook: &Webhook,
+        joke: bool,
+    ) -> Result<Vec<String>> {
+        let selected = if let Some(algs) = self.try_algorithms(webhook, joke).await? {
+            algs
         } else {
-            let algs = if joke { JOKE_ALGORITHMS } else { ALGORITHMS };
-
-            if let Some(other_algs) = self.try_algorithms(webhook, !joke).await? {
-                Ok(algs
-                    .iter()
-                    .copied()
-                    .filter(|x| !other_algs.contains(x))
-                    .collect())
+            let category = if joke {
+                Category::Joke
             } else {
-                Ok(algs.iter().copied().collect())
+                Category::Serious
+            };
+            let other_algs = self
+                .try_algorithms(webhook, !joke)
+                .await?
+                .unwrap_or_default();
+            registry
+                .category(category)
+                .map(|x| x.metadata().slug.to_string())
+                .filter(|x| !other_algs.contains(x))
+                .collect()
+        };
+
+        for slug in &selected {
+            if registry.get(slug).is_none() {
+                warn!("Unknown algorithm for webhook {}: {:?}", webhook.id, slug);
             }
         }
+
+        Ok(registry
+            .iter()
+            .map(|x| x.metadata().slug.to_string())
+            .filter(|x| selected.contains(x))
+            .collect())
     }
 
     pub async fn count(&self) -> Result<i32> {
@@ -100,6 +120,18 @@ impl Database {
         Ok(())
     }
 
+    pub async fn add_algorithm(&self, url: &str, slug: &str, joke: bool) -> Result<()> {
+        sqlx::query!(
+            "INSERT INTO algorithms (algorithm, joke, webhook_id) SELECT ?, ?, id FROM webhooks WHERE url = ?",
+            slug,
+            joke,
+            url,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn remove_url(&self, url: &str) -> Result<()> {
         sqlx::query!("DELETE FROM webhooks WHERE url = ?", url)
             .execute(&self.pool)
@@ -1,7 +1,10 @@
 use super::{db::Database, send_hook};
 use anyhow::Result;
 use async_std::prelude::*;
+use idol_api::decode;
 use idol_api::models::Event;
+use idol_api::provider::HttpProvider;
+use idol_predictor::Registry;
 use log::*;
 use std::time::{Duration, Instant};
 
@@ -51,7 +54,7 @@ impl Client {
             match self.decoder.next().await {
                 Some(Ok(async_sse::Event::Message(message))) => {
                     debug!("Received event");
-                    let data: Event = match serde_json::from_slice(&message.data()) {
+                    let data: Event = match decode("event", message.data()).await {
                         Ok(data) => {
                             debug!("Parsed event");
                             data
@@ -93,39 +96,43 @@ impl Client {
         }
     }
 
-    pub fn run(mut self, db: &Database) -> impl Future<Output = Result<()>> {
+    pub fn run(
+        mut self,
+        db: &Database,
+        provider: &HttpProvider,
+        registry: &Registry,
+    ) -> impl Future<Output = Result<()>> {
         let db = db.clone();
+        let provider = provider.clone();
+        let registry = registry.clone();
         async move {
             loop {
                 let mut data = self.next_event().await?;
-                debug!("Phase {}", data.value.games.sim.phase);
-                match data.value.games.sim.phase {
-                    9 | 10 | 11 => {
-                        debug!("Postseason");
-                        if !data.value.games.tomorrow_schedule.is_empty() {
-                            debug!("Betting allowed");
-                            send_hook(&db, &data, true, None).await?;
-                        } else {
-                            debug!("No betting");
-                        }
-                        while !data.value.games.tomorrow_schedule.is_empty() {
-                            debug!("Waiting for games to start...");
-                            data = self.next_event().await?;
-                        }
-                        debug!("Games in progress");
+                let phase = data.value.games.sim.phase;
+                debug!("Phase {:?}", phase);
+                if phase.is_postseason() {
+                    debug!("Postseason");
+                    if data.value.games.betting_open() {
+                        debug!("Betting allowed");
+                        send_hook(&db, &provider, &registry, &data, true, None).await?;
+                    } else {
+                        debug!("No betting");
                     }
-                    2 | 4 | 6 => {
-                        debug!("Regular season");
-                        send_hook(&db, &data, true, None).await?;
-                        let day = data.value.games.sim.day;
-                        while data.value.games.sim.day == day {
-                            debug!("Waiting for next day...");
-                            data = self.next_event().await?;
-                        }
+                    while !data.value.games.tomorrow_schedule.is_empty() {
+                        debug!("Waiting for games to start...");
+                        data = self.next_event().await?;
                     }
-                    _ => {
-                        debug!("Not season");
+                    debug!("Games in progress");
+                } else if phase.is_regular_season() {
+                    debug!("Regular season");
+                    send_hook(&db, &provider, &registry, &data, true, None).await?;
+                    let day = data.value.games.sim.day;
+                    while data.value.games.sim.day == day {
+                        debug!("Waiting for next day...");
+                        data = self.next_event().await?;
                     }
+                } else {
+                    debug!("Not season");
                 }
             }
         }
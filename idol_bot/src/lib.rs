@@ -1,13 +1,18 @@
 use anyhow::{ensure, Result};
+use async_std::task;
 use db::Database;
 use futures::prelude::*;
 use idol_api::models::Event;
-use idol_api::State;
-use idol_predictor::algorithms::ALL_ALGORITHMS;
+use idol_api::provider::DataProvider;
+use idol_api::snapshot::Snapshot;
+use idol_api::{Dataset, State, StateOptions};
+use idol_predictor::{Algorithm, Category, Registry};
 use log::*;
 use rand::prelude::*;
 use serde::Serialize;
+use std::collections::HashMap;
 use std::fmt::Write;
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
 
@@ -22,16 +27,72 @@ pub struct WebhookPayload<'a> {
     pub avatar_url: &'static str,
 }
 
-async fn get_best(data: &Event) -> Result<Vec<Option<String>>> {
+struct Best {
+    algorithms: HashMap<String, Option<String>>,
+    missing: Vec<Dataset>,
+}
+
+async fn get_best(data: &Event, provider: &dyn DataProvider, registry: &Registry) -> Result<Best> {
     debug!("Building state");
-    let state = State::from_event(data).await?;
+    let options = StateOptions::default();
+    let state = if let Ok(dir) = dotenv::var("SNAPSHOT_DIR") {
+        let (state, snapshot) = Snapshot::record_event(data, provider, &options).await?;
+        let mut path = PathBuf::from(dir);
+        path.push(format!(
+            "s{}d{}",
+            data.value.games.sim.season, data.value.games.sim.day
+        ));
+        path.set_extension("json.gz");
+        debug!("Saving snapshot to {}", path.display());
+        if let Err(err) = task::spawn_blocking(move || snapshot.save(&path)).await {
+            warn!("Failed to save snapshot: {}", err);
+        }
+        state
+    } else {
+        State::from_event(data, provider, &options).await?
+    };
+
+    Ok(Best {
+        algorithms: best_from_state(registry, &state).into_iter().collect(),
+        missing: state.missing().to_vec(),
+    })
+}
+
+pub fn load_registry() -> Result<Registry> {
+    let mut registry = Registry::builtin();
+    if dotenv::var("BATTER_ALGORITHMS").is_ok() {
+        debug!("Registering batter algorithms");
+        registry.register_batters()?;
+    }
+    if let Ok(path) = dotenv::var("ALGORITHMS_FILE") {
+        debug!("Loading custom algorithms from {}", path);
+        registry.load_formulas(&path)?;
+    }
+    Ok(registry)
+}
+
+pub fn skip_missing(algorithm: &dyn Algorithm, state: &State) -> bool {
+    let metadata = algorithm.metadata();
+    match metadata.missing_dataset(state) {
+        Some(dataset) => {
+            warn!("Skipping {}, missing {}", metadata.slug, dataset);
+            true
+        }
+        None => false,
+    }
+}
 
-    Ok(ALL_ALGORITHMS
+pub fn best_from_state(registry: &Registry, state: &State) -> Vec<(String, Option<String>)> {
+    registry
         .iter()
         .map(|algorithm| {
-            debug!("{}", algorithm.name);
+            let metadata = algorithm.metadata();
+            debug!("{}", metadata.name);
+            if skip_missing(algorithm, state) {
+                return (metadata.slug.to_string(), None);
+            }
             let mut text = String::new();
-            match algorithm.write_best_to(&state, &mut text) {
+            let best = match algorithm.write_best_to(state, &mut text) {
                 Ok(_) => {
                     debug!("Succeeded");
                     Some(text)
@@ -40,9 +101,10 @@ async fn get_best(data: &Event) -> Result<Vec<Option<String>>> {
                     warn!("Algorithm failed: {}", err);
                     None
                 }
-            }
+            };
+            (metadata.slug.to_string(), best)
         })
-        .collect())
+        .collect()
 }
 
 async fn send_message(db: &Database, url: &str, content: &str) -> Result<()> {
@@ -69,6 +131,8 @@ async fn send_message(db: &Database, url: &str, content: &str) -> Result<()> {
 
 pub fn send_hook<'a>(
     db: &'a Database,
+    provider: &'a dyn DataProvider,
+    registry: &'a Registry,
     data: &'a Event,
     retry: bool,
     test_mode: Option<usize>,
@@ -76,18 +140,31 @@ pub fn send_hook<'a>(
     Box::pin(async move {
         let day = data.value.games.sim.day + 2;
 
-        let best: Arc<[_]> = match get_best(data).await {
-            Ok(content) => Arc::from(content),
+        let best = match get_best(data, provider, registry).await {
+            Ok(best) => Arc::new(best),
             Err(err) => {
                 warn!("Failed to get best: {}", err);
                 if retry {
                     debug!("Retrying...");
-                    return send_hook(db, data, false, test_mode).await;
+                    return send_hook(db, provider, registry, data, false, test_mode).await;
                 } else if test_mode.is_some() {
                     debug!("Sending test message");
-                    Arc::from(vec![Some(
-                        "Error getting best idols, ignoring due to test mode".into(),
-                    )])
+                    Arc::new(Best {
+                        algorithms: registry
+                            .category(Category::Serious)
+                            .take(1)
+                            .map(|x| {
+                                (
+                                    x.metadata().slug.to_string(),
+                                    Some(
+                                        "Error getting best idols, ignoring due to test mode"
+                                            .into(),
+                                    ),
+                                )
+                            })
+                            .collect(),
+                        missing: Vec::new(),
+                    })
                 } else {
                     debug!("Not retrying");
                     return Err(err.context("Failed to get best"));
@@ -112,18 +189,18 @@ pub fn send_hook<'a>(
                         writeln!(content, "**Day {}**", day)?;
                     }
 
-                    let algorithms = db.algorithms(&webhook, false).await?;
+                    let algorithms = db.algorithms(registry, &webhook, false).await?;
 
                     for algorithm in algorithms {
-                        if let Some(Some(best)) = best.get(algorithm as usize) {
+                        if let Some(Some(best)) = best.algorithms.get(&algorithm) {
                             write!(content, "{}", best)?;
                         }
                     }
 
-                    let joke_algorithm_ids = db.algorithms(&webhook, true).await?;
+                    let joke_algorithm_ids = db.algorithms(registry, &webhook, true).await?;
                     let joke_algorithms = joke_algorithm_ids
                         .iter()
-                        .map(|&x| best.get(x as usize).map(Option::as_ref).flatten());
+                        .map(|x| best.algorithms.get(x).and_then(Option::as_ref));
                     let joke_algorithm = test_mode
                         .and_then(|x| {
                             info!("TEST MODE: using algorithm #{}", x);
@@ -134,14 +211,23 @@ pub fn send_hook<'a>(
                         write!(content, "{}", joke_algorithm)?;
                     }
 
-                    match send_message(&db, &webhook.url, &content).await {
+                    if !best.missing.is_empty() {
+                        let missing = best
+                            .missing
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>();
+                        writeln!(content, "*Data missing: {}*", missing.join(", "))?;
+                    }
+
+                    match send_message(db, &webhook.url, &content).await {
                         Ok(_) => {
                             debug!("Sent");
                         }
                         Err(err) => {
                             warn!("Failed to send message: {}", err);
                             debug!("Retrying...");
-                            match send_message(&db, &webhook.url, &content).await {
+                            match send_message(db, &webhook.url, &content).await {
                                 Ok(_) => {
                                     debug!("Sent");
                                 }
@@ -1,25 +1,47 @@
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use async_std::prelude::*;
 use async_std::task;
+use idol_api::provider::HttpProvider;
+use idol_api::{DataSources, ResponseCache};
 use idol_bot::{
     db::Database,
     events::Client,
-    logger,
+    load_registry, logger,
     oauth_listener::{self, OAuth},
     send_hook,
 };
 use log::*;
+use std::time::Duration;
 
 #[async_std::main]
 async fn main() -> Result<()> {
     logger::init()?;
 
     let test_mode: Option<usize> = dotenv::var("TEST_MODE").ok().and_then(|x| x.parse().ok());
-    let stream_url = "https://www.blaseball.com/events/streamData";
+    let sources = DataSources::from_env()?;
+    let stream_url = sources.blaseball("events/streamData");
+    let mut provider = HttpProvider::new(sources);
+    if let Some(limit) = dotenv::var("MAX_CONCURRENT_REQUESTS")
+        .ok()
+        .and_then(|x| x.parse::<usize>().ok())
+    {
+        ensure!(limit > 0, "MAX_CONCURRENT_REQUESTS must be at least 1");
+        provider = provider.with_concurrency(limit);
+    }
+    if let Some(secs) = dotenv::var("REQUEST_TIMEOUT")
+        .ok()
+        .and_then(|x| x.parse().ok())
+    {
+        provider = provider.with_timeout(Duration::from_secs(secs));
+    }
+    if let Some(cache) = ResponseCache::from_env() {
+        provider = provider.with_cache(cache);
+    }
 
     let db_uri = dotenv::var("DATABASE_URL")?;
 
     let db = Database::connect(&db_uri).await?;
+    let registry = load_registry()?;
     debug!("Connected to database");
 
     let redirect_uri = dotenv::var("REDIRECT_URI")?;
@@ -37,16 +59,16 @@ async fn main() -> Result<()> {
     )
     .await?;
 
-    let mut client = Client::connect(stream_url).await?;
+    let mut client = Client::connect(&stream_url).await?;
     debug!("Connected to Blaseball");
 
     if let Some(test_mode) = test_mode {
         let data = client.next_event().await?;
-        debug!("Phase {}", data.value.games.sim.phase);
+        debug!("Phase {:?}", data.value.games.sim.phase);
         info!("TESTING MODE");
-        send_hook(&db, &data, false, Some(test_mode)).await?;
+        send_hook(&db, &provider, &registry, &data, false, Some(test_mode)).await?;
     } else {
-        let bot = task::spawn(client.run(&db));
+        let bot = task::spawn(client.run(&db, &provider, &registry));
         let listener = task::spawn(oauth_listener::listen(
             &db,
             OAuth {
@@ -0,0 +1,40 @@
+use anyhow::Result;
+use sqlx::sqlite::SqlitePoolOptions;
+
+#[async_std::test]
+async fn keeps_unknown_algorithms_when_migrating_to_slugs() -> Result<()> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await?;
+    let mut migrator = sqlx::migrate!("./migrations");
+    let migrations = migrator.migrations.to_vec();
+    migrator.migrations = migrations
+        .iter()
+        .filter(|x| x.version < 20210310180000)
+        .cloned()
+        .collect::<Vec<_>>()
+        .into();
+    migrator.run(&pool).await?;
+
+    // The original algorithms table references a nonexistent `webhook` table.
+    sqlx::query("PRAGMA foreign_keys = OFF")
+        .execute(&pool)
+        .await?;
+    sqlx::query("INSERT INTO webhooks (id, url) VALUES (1, 'https://example.com')")
+        .execute(&pool)
+        .await?;
+    sqlx::query("INSERT INTO algorithms VALUES (5, TRUE, 1), (42, FALSE, 1)")
+        .execute(&pool)
+        .await?;
+    sqlx::query("PRAGMA foreign_keys = ON")
+        .execute(&pool)
+        .await?;
+    sqlx::migrate!("./migrations").run(&pool).await?;
+
+    let slugs: Vec<String> = sqlx::query_scalar("SELECT algorithm FROM algorithms ORDER BY rowid")
+        .fetch_all(&pool)
+        .await?;
+    assert_eq!(slugs, ["best_best", "unknown_42"]);
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use anyhow::Result;
+use idol_api::provider::HttpProvider;
+use idol_bot::{db::Database, events::Client, send_hook, skip_missing};
+use idol_predictor::Registry;
+use idol_test_support::{Fixtures, MockServer};
+
+#[async_std::test]
+async fn sends_predictions_to_webhooks() -> Result<()> {
+    let server = MockServer::start(Fixtures::sample()).await?;
+    let provider = HttpProvider::new(server.sources());
+    let db = Database::connect("sqlite::memory:").await?;
+    db.add_url(&server.webhook_url("crabs")).await?;
+
+    let mut client = Client::connect(&server.stream_url()).await?;
+    let data = client.next_event().await?;
+    assert_eq!(data.value.games.sim.day, 9);
+
+    send_hook(&db, &provider, &Registry::builtin(), &data, false, None).await?;
+
+    let messages = server.webhooks();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].hook, "crabs");
+    assert!(messages[0].content.starts_with("**Day 11**"));
+    assert!(messages[0].content.contains("Wyatt Best"));
+    assert!(!messages[0].content.contains("Data missing"));
+    Ok(())
+}
+
+#[async_std::test]
+async fn removes_deleted_webhooks() -> Result<()> {
+    let fixtures = Fixtures::sample();
+    let data = fixtures.events[0].clone();
+    let server = MockServer::start(fixtures).await?;
+    let provider = HttpProvider::new(server.sources());
+    let db = Database::connect("sqlite::memory:").await?;
+    db.add_url(&server.webhook_url("gone")).await?;
+    db.add_url(&server.webhook_url("crabs")).await?;
+
+    send_hook(&db, &provider, &Registry::builtin(), &data, false, None).await?;
+
+    assert_eq!(db.count().await?, 1);
+    assert_eq!(server.webhooks().len(), 1);
+    Ok(())
+}
+
+#[async_std::test]
+async fn notes_missing_data() -> Result<()> {
+    let mut fixtures = Fixtures::sample();
+    fixtures.data.missing.push(idol_api::Dataset::Idols);
+    let data = fixtures.events[0].clone();
+    let server = MockServer::start(fixtures).await?;
+    let provider = HttpProvider::new(server.sources());
+    let db = Database::connect("sqlite::memory:").await?;
+    db.add_url(&server.webhook_url("crabs")).await?;
+
+    send_hook(&db, &provider, &Registry::builtin(), &data, false, None).await?;
+
+    let messages = server.webhooks();
+    assert!(messages[0].content.contains("*Data missing: idols*"));
+    Ok(())
+}
+
+#[async_std::test]
+async fn sends_selected_algorithms() -> Result<()> {
+    let fixtures = Fixtures::sample();
+    let data = fixtures.events[0].clone();
+    let server = MockServer::start(fixtures).await?;
+    let provider = HttpProvider::new(server.sources());
+    let db = Database::connect("sqlite::memory:").await?;
+    let url = server.webhook_url("crabs");
+    db.add_url(&url).await?;
+    db.add_algorithm(&url, "ruthlessness", false).await?;
+    db.add_algorithm(&url, "removed_algorithm", false).await?;
+
+    send_hook(&db, &provider, &Registry::builtin(), &data, false, None).await?;
+
+    let messages = server.webhooks();
+    assert!(messages[0].content.contains("Best by ruthlessness"));
+    assert!(!messages[0].content.contains("Best by SO/9"));
+    Ok(())
+}
+
+#[async_std::test]
+async fn skips_algorithms_missing_datasets() -> Result<()> {
+    let mut fixtures = Fixtures::sample();
+    fixtures.data.missing.push(idol_api::Dataset::Idols);
+    let state = idol_api::State::from_event(
+        &fixtures.events[0],
+        &fixtures.data,
+        &idol_api::StateOptions::default(),
+    )
+    .await?;
+    let registry = Registry::builtin();
+
+    assert!(skip_missing(registry.get("idols").unwrap(), &state));
+    assert!(!skip_missing(registry.get("ruthlessness").unwrap(), &state));
+    Ok(())
+}
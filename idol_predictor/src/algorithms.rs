@@ -1,404 +1,453 @@
-use super::{Algorithm, Forbidden::*, PitcherRef, PrintedStat, ScoredPitcher, Strategy::*};
-use anyhow::{anyhow, bail};
-use average::Mean;
-use idol_api::models::{Game, Team};
+use super::{
+    Builtin, Candidate, Category, Explanation, Forbidden::*, Metadata, PrintedStat, Role,
+    ScoredCandidate, Strategy::*,
+};
+use anyhow::{anyhow, Result};
+use idol_api::models::{FeedEventType, Game, GameId, Position, Team, TeamId, Weather};
 use idol_api::team_pair::TeamPosition;
-use noisy_float::prelude::*;
+use idol_api::{Dataset, State};
 use paste::paste;
 use std::borrow::Cow;
 
 macro_rules! algorithm {
-    ($id:ident, _, [$($stat:ident),*], $forbidden:ident, $($strat:tt)*) => {
+    ($id:ident, $slug:literal, _, [$($stat:ident),*], $forbidden:ident, $($strat:tt)*) => {
         paste! {
-            algorithm!($id, stringify!([<$id:lower>]), [$($stat),*], $forbidden, $($strat)*);
+            algorithm!($id, $slug, stringify!([<$id:lower>]), [$($stat),*], $forbidden, $($strat)*);
         }
     };
 
-    ($id:ident, $name:expr, [$($stat:ident),*], $forbidden:ident, $($strat:tt)*) => {
-        algorithm!($id, @ concat!("Best by ", $name), [$($stat),*], $forbidden, $($strat)*);
+    ($id:ident, $slug:literal, $name:expr, [$($stat:ident),*], $forbidden:ident, $($strat:tt)*) => {
+        algorithm!($id, $slug, @ concat!("Best by ", $name), [$($stat),*], $forbidden, $($strat)*);
     };
 
-    ($id:ident, @ $name:expr, [$($stat:ident),*], $forbidden:ident, |$x:ident| $strat:expr) => {
+    ($id:ident, $slug:literal, @ $name:expr, [$($stat:ident),*], $forbidden:ident, $category:ident, [$($dataset:tt)*], $description:expr, $role:ident => Explain(|$x:ident| $strat:expr)) => {
         paste! {
-            pub fn [<best_by_ $id:lower>]($x: PitcherRef) -> Option<f64> {
+            pub fn [<explain_ $id:lower>]($x: Candidate) -> Option<Explanation> {
                 Some($strat)
             }
 
-            algorithm!($id, @ $name, [$($stat),*], $forbidden, Maximize([<best_by_ $id:lower>]));
+            algorithm!($id, $slug, @ $name, [$($stat),*], $forbidden, $category, [$($dataset)*], $description, $role => Explain([<explain_ $id:lower>]));
         }
     };
 
-    ($id:ident, @ $name:expr, [$($stat:ident),*], $forbidden:ident, $strat:expr) => {
-        pub const $id: Algorithm = Algorithm {
-            name: $name,
-            forbidden: $forbidden,
-            printed_stats: &[$(PrintedStat::$stat),*],
-            strategy: $strat,
-        };
+    ($id:ident, $slug:literal, @ $name:expr, [$($stat:ident),*], $forbidden:ident, $category:ident, [$($dataset:tt)*], $description:expr, $role:ident => |$x:ident| $strat:expr) => {
+        paste! {
+            pub fn [<best_by_ $id:lower>]($x: Candidate) -> Option<f64> {
+                Some($strat)
+            }
+
+            algorithm!($id, $slug, @ $name, [$($stat),*], $forbidden, $category, [$($dataset)*], $description, $role => Maximize([<best_by_ $id:lower>]));
+        }
+    };
+
+    ($id:ident, $slug:literal, @ $name:expr, [$($stat:ident),*], $forbidden:ident, $category:ident, [$($dataset:ident$(($kind:ident))?),*], $description:expr, $role:ident => $strat:expr) => {
+        paste! {
+            pub const $id: Builtin = Builtin {
+                metadata: Metadata {
+                    slug: Cow::Borrowed($slug),
+                    name: Cow::Borrowed($name),
+                    description: Cow::Borrowed($description),
+                    category: Category::$category,
+                    role: Role::$role,
+                    forbidden: $forbidden,
+                    printed_stats: Cow::Borrowed(&[$(PrintedStat::$stat),*]),
+                    datasets: Cow::Borrowed(&[$(Dataset::$dataset$((FeedEventType::$kind))?),*]),
+                },
+                strategy: $strat,
+            };
+        }
+    };
+
+    ($id:ident, $slug:literal, @ $name:expr, [$($stat:ident),*], $forbidden:ident, $category:ident, [$($dataset:tt)*], $description:expr, $($strat:tt)*) => {
+        algorithm!($id, $slug, @ $name, [$($stat),*], $forbidden, $category, [$($dataset)*], $description, Pitcher => $($strat)*);
     };
 }
 
-algorithm!(SO9, "SO/9", [], Unforbidden, |x| x.stats?.strikeouts_per_9);
+algorithm!(
+    SO9,
+    "so9",
+    "SO/9",
+    [],
+    Unforbidden,
+    Serious,
+    [PitcherStats],
+    "This uses the current-season SO/9 to calculate the pitcher likely to score the most strikeouts.",
+    |x| x.pitching_stats?.strikeouts_per_9
+);
 
-algorithm!(RUTHLESSNESS, _, [SO9], Forbidden, |x| x.player.ruthlessness);
+algorithm!(
+    RUTHLESSNESS,
+    "ruthlessness",
+    _,
+    [SO9],
+    Forbidden,
+    Serious,
+    [],
+    "This uses ruthlessness as a proxy for SO/9.",
+    |x| x.player.ruthlessness
+);
 
-algorithm!(STAT_RATIO, "(SO/9)(SO/AB)", [SO9], Unforbidden, |x| {
-    x.stats?.strikeouts_per_9
-        * (0.2
-            + x.opponent
-                .strikeouts(x.state)
-                .zip(x.opponent.at_bats(x.state))
-                .map(|(so, ab)| Some((so?, ab?)))
-                .map(|x| x.map(|(so, ab)| so as f64 / ab as f64))
-                .collect::<Option<Mean>>()?
-                .mean())
-});
+algorithm!(
+    STAT_RATIO,
+    "stat_ratio",
+    "(SO/9)(SO/AB)",
+    [SO9],
+    Unforbidden,
+    Serious,
+    [PitcherStats, Strikeouts, AtBats],
+    "This uses the current-season SO/9 and SO/AB to calculate the pitcher likely to score the most strikeouts against the opposing team.",
+    Explain(|x| {
+        let so9 = x.pitching_stats?.strikeouts_per_9;
+        let so_ab = x.opponent_strikeout_rate()?;
+        Explanation::new(so9 * (0.2 + so_ab))
+            .with("SO/9", so9)
+            .with("opponent SO/AB", so_ab)
+    })
+);
 
 algorithm!(
     BESTNESS,
+    "bestness",
     "Bestness",
     [],
     Unforbidden,
+    Joke,
+    [],
+    "This chooses a player based on the percentage of their name that is the string \"Best.\"",
     Custom(|state| {
-        let (position, score) = state
-            .players
+        let ranked = state
+            .players()
             .iter()
-            .filter(|x| x.data.name.contains("Best"))
-            .map(|x| (x, 4.0 / x.data.name.len() as f64))
-            .max_by_key(|x| n64(x.1))
-            .ok_or_else(|| anyhow!("No Best player!"))?;
-        let game = state
-            .games
-            .iter()
-            .find(|x| {
-                x.home_team == position.data.league_team_id
-                    || x.away_team == position.data.league_team_id
+            .filter(|x| x.data.name.contains("Best") && in_game(x, state))
+            .map(|x| {
+                let candidate = Candidate::for_player(x, state, Role::Pitcher)?;
+                Ok(ScoredCandidate::new(
+                    candidate,
+                    4.0 / x.data.name.len() as f64,
+                ))
             })
-            .ok_or_else(|| anyhow!("No Best game!"))?;
-        let teams = game
-            .teams(state)
-            .ok_or_else(|| anyhow!("Couldn't get teams!"))?;
-        let (team, opponent, team_pos) = if teams.away.id == position.data.league_team_id {
-            (teams.away, teams.home, TeamPosition::Away)
-        } else {
-            (teams.home, teams.away, TeamPosition::Home)
-        };
-        let id = &position.data.id;
-        let player = &position.data;
-        let pitcher = PitcherRef {
-            id,
-            position,
-            player,
-            stats: None,
-            game,
-            state,
-            team,
-            opponent,
-            team_pos,
-        };
-        Ok(ScoredPitcher { pitcher, score })
+            .collect::<Result<_>>()?;
+        Ok(ScoredCandidate::sorted(ranked))
     })
 );
 
-const CRABS: &str = "8d87c468-699a-47a8-b40d-cfb73a5660ad";
+const CRABS: TeamId = TeamId::from_u128(0x8d87c468_699a_47a8_b40d_cfb73a5660ad);
+
+fn in_game(position: &Position, state: &State) -> bool {
+    state.games().iter().any(|x| {
+        x.team_ids()
+            .any(|y| Some(y) == position.data.league_team_id)
+    })
+}
+
+fn black_hole<'a>(position: &'a Position, state: &'a State) -> Result<Candidate<'a>> {
+    let team = state.team(CRABS).ok_or_else(|| anyhow!("Missing Crabs!"))?;
+    static BLACK_HOLE: Team = Team {
+        id: TeamId::nil(),
+        full_name: Cow::Borrowed("Black Hole"),
+        lineup: Vec::new(),
+        rotation: Vec::new(),
+        bullpen: Vec::new(),
+        bench: Vec::new(),
+        perm_attr: Vec::new(),
+    };
+    let team_pos = TeamPosition::Away;
+    static GAME: Game = Game {
+        id: GameId::nil(),
+        away_pitcher: None,
+        away_pitcher_name: None,
+        home_pitcher: None,
+        home_pitcher_name: None,
+        away_team: CRABS,
+        away_team_name: Cow::Borrowed("Baltimore Crabs"),
+        home_team: TeamId::nil(),
+        home_team_name: Cow::Borrowed("Black Hole"),
+        away_odds: 0.0,
+        home_odds: 0.0,
+        inning: 0,
+        day: 0,
+        season: 0,
+        weather: Some(Weather::BlackHole),
+        stadium_id: None,
+        series_index: 0,
+        series_length: 0,
+        is_postseason: false,
+        is_title_match: false,
+        is_prize_match: false,
+        home_strikes: None,
+        away_strikes: None,
+        home_balls: None,
+        away_balls: None,
+        home_bases: None,
+        away_bases: None,
+        home_outs: None,
+        away_outs: None,
+        outcomes: Vec::new(),
+    };
+    Ok(Candidate {
+        id: position.id,
+        position,
+        player: &position.data,
+        role: Role::Pitcher,
+        pitching_stats: None,
+        batting_stats: None,
+        game: &GAME,
+        state,
+        team,
+        opponent: &BLACK_HOLE,
+        team_pos,
+    })
+}
 
 algorithm!(
     BEST_BEST,
+    "best_best",
     @ "Best Best by Stars",
     [],
     Unforbidden,
+    Joke,
+    [],
+    "This chooses the player with the most pitching stars, limited to names containing the string \"Best.\"",
     Custom(|state| {
-        let (position, score) = state
-            .players
+        let ranked = state
+            .players()
             .iter()
             .filter(|x| x.data.name.contains("Best"))
-            .map(|x| (x, (x.data.pitching_rating * 10.0).floor() / 2.0))
-            .max_by_key(|x| n64(x.1))
-            .ok_or_else(|| anyhow!("No Best player!"))?;
-        let game = state.games.iter().find(|x| {
-            x.home_team == position.data.league_team_id
-                || x.away_team == position.data.league_team_id
-        });
-        if let Some(game) = game {
-            let teams = game
-                .teams(state)
-                .ok_or_else(|| anyhow!("Couldn't get teams!"))?;
-            let (team, opponent, team_pos) = if teams.away.id == position.data.league_team_id {
-                (teams.away, teams.home, TeamPosition::Away)
-            } else {
-                (teams.home, teams.away, TeamPosition::Home)
-            };
-            let id = &position.data.id;
-            let player = &position.data;
-            let pitcher = PitcherRef {
-                id,
-                position,
-                player,
-                stats: None,
-                game,
-                state,
-                team,
-                opponent,
-                team_pos,
-            };
-            Ok(ScoredPitcher { pitcher, score })
-        } else if position.data.league_team_id == CRABS {
-            let team = state
-                .teams
-                .iter()
-                .find(|x| x.id == CRABS)
-                .ok_or_else(|| anyhow!("Missing Crabs!"))?;
-            let id = &position.data.id;
-            let player = &position.data;
-            static BLACK_HOLE: Team = Team {
-                id: String::new(),
-                full_name: Cow::Borrowed("Black Hole"),
-                lineup: Vec::new(),
-                rotation: Vec::new(),
-                bullpen: Vec::new(),
-                bench: Vec::new(),
-                perm_attr: Vec::new(),
-            };
-            let team_pos = TeamPosition::Away;
-            static GAME: Game = Game {
-                id: String::new(),
-                away_pitcher: None,
-                away_pitcher_name: None,
-                home_pitcher: None,
-                home_pitcher_name: None,
-                away_team: Cow::Borrowed(&CRABS),
-                away_team_name: Cow::Borrowed("Baltimore Crabs"),
-                home_team: String::new(),
-                home_team_name: Cow::Borrowed("Black Hole"),
-                away_odds: 0.0,
-                home_odds: 0.0,
-                inning: 0,
-                day: 0,
-                season: 0,
-            };
-            let pitcher = PitcherRef {
-                id,
-                position,
-                player,
-                stats: None,
-                game: &GAME,
-                state,
-                team,
-                opponent: &BLACK_HOLE,
-                team_pos,
-            };
-            Ok(ScoredPitcher { pitcher, score })
-        } else {
-            bail!("No Best game!");
-        }
+            .filter_map(|x| Some((x, (x.data.pitching_rating? * 10.0).floor() / 2.0)))
+            .filter_map(|(x, score)| {
+                let candidate = if in_game(x, state) {
+                    Candidate::for_player(x, state, Role::Pitcher)
+                } else if x.data.league_team_id == Some(CRABS) {
+                    black_hole(x, state)
+                } else {
+                    return None;
+                };
+                Some(candidate.map(|y| ScoredCandidate::new(y, score)))
+            })
+            .collect::<Result<_>>()?;
+        Ok(ScoredCandidate::sorted(ranked))
     })
 );
 
-const LIFT_ID: &str = "c73b705c-40ad-4633-a6ed-d357ee2e2bcf";
+const LIFT_ID: TeamId = TeamId::from_u128(0xc73b705c_40ad_4633_a6ed_d357ee2e2bcf);
 
-algorithm!(LIFT, @ "Against Lift", [], Unforbidden, |x| if x.opponent.id == LIFT_ID { 1.0 } else { 0.0 });
+algorithm!(
+    LIFT,
+    "lift",
+    @ "Against Lift",
+    [],
+    Unforbidden,
+    Joke,
+    [],
+    "This chooses a pitcher based on the number of teams named \"Tokyo Lift\" that the pitcher is against.",
+    |x| if x.opponent.id == LIFT_ID { 1.0 } else { 0.0 }
+);
 
-algorithm!(WORST_STAT_RATIO, @ "Worst by (-SO/9)/(SO/AB)", [SO9], Unforbidden, |x| {
-    -x.stats?.strikeouts_per_9
-        / x.opponent
-                .strikeouts(x.state)
-                .zip(x.opponent.at_bats(x.state))
-                .map(|(so, ab)| Some((so?, ab?)))
-                .map(|x| x.map(|(so, ab)| so as f64 / ab as f64))
-                .collect::<Option<Mean>>()?
-                .mean()
-});
+algorithm!(
+    WORST_STAT_RATIO,
+    "worst_stat_ratio",
+    @ "Worst by (-SO/9)/(SO/AB)",
+    [SO9],
+    Unforbidden,
+    Joke,
+    [PitcherStats, Strikeouts, AtBats],
+    "This is the inverse of best by (SO/9)/(SO/AB).",
+    Explain(|x| {
+        let so9 = x.pitching_stats?.strikeouts_per_9;
+        let so_ab = x.opponent_strikeout_rate()?;
+        Explanation::new(-so9 / so_ab)
+            .with("SO/9", so9)
+            .with("opponent SO/AB", so_ab)
+    })
+);
 
-algorithm!(IDOLS, "idolization", [], Unforbidden, |x| {
-    -(x.state
-        .idols
-        .iter()
-        .position(|y| y.player_id == x.player.id)
-        .unwrap_or(20) as f64)
-        - 1.0
-});
+algorithm!(
+    IDOLS,
+    "idols",
+    "idolization",
+    [],
+    Unforbidden,
+    Joke,
+    [Idols],
+    "This chooses the pitcher with the highest position on the idol leaderboard.",
+    |x| {
+        -(x.state
+            .idols()
+            .iter()
+            .position(|y| y.player_id == x.player.id)
+            .unwrap_or(20) as f64)
+            - 1.0
+    }
+);
 
-algorithm!(BATTING_STARS, "batting stars", [], Unforbidden, |x| {
-    (x.player.hitting_rating * 10.0).floor() / 2.0
-});
+algorithm!(
+    BATTING_STARS,
+    "batting_stars",
+    "batting stars",
+    [],
+    Unforbidden,
+    Joke,
+    [],
+    "This chooses a pitcher based on batting stars.",
+    |x| (x.player.hitting_rating? * 10.0).floor() / 2.0
+);
 
-algorithm!(NAME_LENGTH, "name length", [], Unforbidden, |x| {
-    x.player.name.len() as f64
-});
+algorithm!(
+    NAME_LENGTH,
+    "name_length",
+    "name length",
+    [],
+    Unforbidden,
+    Joke,
+    [],
+    "This chooses a pitcher based on the number of characters in their name.",
+    |x| x.player.name.len() as f64
+);
 
-algorithm!(GAMES_PER_GAME, "games per game", [], Unforbidden, |x| {
-    let normal_games = x.stats?.games;
-    let extra = x
-        .state
-        .sun_2
-        .iter()
-        .chain(&x.state.black_hole)
-        .take_while(|y| y.season == x.state.season)
-        .filter_map(|item| {
-            let team = &item.team_tags[0];
-            let game = item.game(&x.state)?;
-            let pitchers = game.pitcher_ids()?;
-            Some((team, game, pitchers))
-        })
-        .filter(|(team, game, pitchers)| {
-            if *team == &game.home_team {
-                pitchers.home == x.id
-            } else {
-                pitchers.away == x.id
-            }
-        })
-        .count();
-    let games = normal_games + extra;
-    games as f64 / normal_games as f64
-});
+algorithm!(
+    GAMES_PER_GAME,
+    "games_per_game",
+    "games per game",
+    [],
+    Unforbidden,
+    Joke,
+    [
+        PitcherStats,
+        PastGames,
+        Feed(Sun2Outcome),
+        Feed(BlackHoleOutcome)
+    ],
+    "This chooses the pitcher who has pitched the most extra games per game this season.",
+    Explain(|x| {
+        let normal_games = Some(x.pitching_stats?.games).filter(|&x| x > 0)?;
+        let extra = x
+            .state
+            .season_feed_events(FeedEventType::Sun2Outcome)
+            .chain(x.state.season_feed_events(FeedEventType::BlackHoleOutcome))
+            .filter_map(|item| {
+                let team = *item.team_tags.first()?;
+                let game = item.game(x.state)?;
+                let pitchers = game.pitcher_ids()?;
+                Some((team, game, pitchers))
+            })
+            .filter(|(team, game, pitchers)| {
+                if *team == game.home_team {
+                    pitchers.home == x.id
+                } else {
+                    pitchers.away == x.id
+                }
+            })
+            .count();
+        let games = normal_games + extra;
+        Explanation::new(games as f64 / normal_games as f64)
+            .with("games", normal_games as f64)
+            .with("extra games", extra as f64)
+    })
+);
 
 algorithm!(
     GAMES_NAME_PER_GAME,
+    "games_name_per_game",
     "Games per game",
     [],
     Unforbidden,
+    Joke,
+    [],
+    "This chooses the team with the most pitchers whose names contain the string \"Game.\"",
     Custom(|state| {
-        let game = state
-            .games
+        state
+            .games()
             .iter()
-            .find(|x| {
-                x.pitcher_names()
-                    .map(|y| y.any(|z| z.contains("Games")))
-                    .unwrap_or(false)
+            .flat_map(|x| x.pitcher_positions(state).into_iter().flatten())
+            .filter(|x| x.data.name.contains("Games"))
+            .map(|x| {
+                let candidate = Candidate::for_player(x, state, Role::Pitcher)?;
+                Ok(ScoredCandidate::new(candidate, 1.0))
             })
-            .ok_or_else(|| anyhow!("No Games game!"))?;
-        let position = game
-            .pitcher_positions(state)
-            .into_iter()
-            .flatten()
-            .find(|x| x.data.name.contains("Games"))
-            .ok_or_else(|| anyhow!("Lost the Games!"))?;
-        let teams = game
-            .teams(state)
-            .ok_or_else(|| anyhow!("Couldn't get teams!"))?;
-        let (team, opponent, team_pos) = if teams.away.id == position.data.league_team_id {
-            (teams.away, teams.home, TeamPosition::Away)
-        } else {
-            (teams.home, teams.away, TeamPosition::Home)
-        };
-        let id = &position.data.id;
-        let player = &position.data;
-        let pitcher = PitcherRef {
-            id,
-            position,
-            player,
-            stats: None,
-            game,
-            state,
-            team,
-            opponent,
-            team_pos,
-        };
-        Ok(ScoredPitcher {
-            pitcher,
-            score: 1.0,
-        })
+            .collect()
     })
 );
 
 algorithm!(
     BATTING_MULTIPLIER,
+    "batting_multiplier",
     @ "Best Batter by Multiplier",
     [],
     Unforbidden,
-    Custom(|state| {
-        let (position, score) = state
-            .players
+    Joke,
+    [],
+    "This chooses the batter with the highest idol multiplier.",
+    Batter => Custom(|state| {
+        let ranked = state
+            .players()
             .iter()
+            .filter(|x| in_game(x, state))
             .filter_map(|x| match &x.data.name[..] {
                 "York Silk" => Some((x, 2.0)),
                 "Wyatt Glover" => Some((x, 5.0)),
                 _ => None,
             })
-            .max_by_key(|x| n64(x.1))
-            .ok_or_else(|| anyhow!("No best multiplier!"))?;
-        let game = state
-            .games
-            .iter()
-            .find(|x| x.home_team == position.data.league_team_id || x.away_team == position.data.league_team_id)
-            .ok_or_else(|| anyhow!("No game with {}!", position.data.name))?;
-        let teams = game
-            .teams(state)
-            .ok_or_else(|| anyhow!("Couldn't get teams!"))?;
-        let (team, opponent, team_pos) = if teams.away.id == position.data.league_team_id {
-            (teams.away, teams.home, TeamPosition::Away)
-        } else {
-            (teams.home, teams.away, TeamPosition::Home)
-        };
-        let id = &position.data.id;
-        let player = &position.data;
-        let pitcher = PitcherRef {
-            id,
-            position,
-            player,
-            stats: None,
-            game,
-            state,
-            team,
-            opponent,
-            team_pos,
-        };
-        Ok(ScoredPitcher { pitcher, score })
+            .map(|(x, score)| {
+                let candidate = Candidate::for_player(x, state, Role::Batter)?;
+                Ok(ScoredCandidate::new(candidate, score))
+            })
+            .collect::<Result<_>>()?;
+        Ok(ScoredCandidate::sorted(ranked))
     })
 );
 
-macro_rules! eat {
-    ($x:expr) => {};
-}
-
-macro_rules! algorithms {
-    (const ALGORITHMS = [$($serious:expr),*]; const JOKE_ALGORITHMS = [$($jokes:expr),*$(,)?];) => {
-        pub const ALL_ALGORITHMS: &[Algorithm] = &[
-            $($serious, )*
-            $($jokes, )*
-        ];
+algorithm!(
+    HITS,
+    "hits",
+    "hits",
+    [Hits],
+    Unforbidden,
+    Joke,
+    [BatterStats],
+    "This chooses the batter in an upcoming game with the most hits this season.",
+    Batter => |x| x.batting_stats?.hits as f64
+);
 
-        #[allow(clippy::eval_order_dependence, unused_assignments)]
-        pub const ALGORITHMS: &[i64] = {
-            let mut i = 0;
-            &[
-                $({
-                    eat!($serious);
-                    let val = i;
-                    i += 1;
-                    val
-                }),*
-            ]
-        };
+algorithm!(
+    HOME_RUNS,
+    "home_runs",
+    "home runs",
+    [HomeRuns],
+    Unforbidden,
+    Joke,
+    [BatterStats],
+    "This chooses the batter in an upcoming game with the most home runs this season.",
+    Batter => |x| x.batting_stats?.home_runs as f64
+);
 
-        #[allow(clippy::eval_order_dependence, unused_assignments)]
-        pub const JOKE_ALGORITHMS: &[i64] = {
-            let mut i = ALGORITHMS.len() as i64;
-            &[
-                $({
-                    eat!($jokes);
-                    let val = i;
-                    i += 1;
-                    val
-                }),*
-            ]
-        };
-    };
-}
+algorithm!(
+    STEALS,
+    "steals",
+    "steals",
+    [StolenBases],
+    Unforbidden,
+    Joke,
+    [BatterStats],
+    "This chooses the batter in an upcoming game with the most stolen bases this season.",
+    Batter => |x| x.batting_stats?.stolen_bases as f64
+);
 
-algorithms! {
-    const ALGORITHMS = [SO9, RUTHLESSNESS, STAT_RATIO];
+pub const ALL_ALGORITHMS: &[Builtin] = &[
+    SO9,
+    RUTHLESSNESS,
+    STAT_RATIO,
+    LIFT,
+    BESTNESS,
+    BEST_BEST,
+    WORST_STAT_RATIO,
+    IDOLS,
+    BATTING_STARS,
+    NAME_LENGTH,
+    GAMES_PER_GAME,
+    GAMES_NAME_PER_GAME,
+    BATTING_MULTIPLIER,
+];
 
-    const JOKE_ALGORITHMS = [
-        LIFT,
-        BESTNESS,
-        BEST_BEST,
-        WORST_STAT_RATIO,
-        IDOLS,
-        BATTING_STARS,
-        NAME_LENGTH,
-        GAMES_PER_GAME,
-        GAMES_NAME_PER_GAME,
-        BATTING_MULTIPLIER,
-    ];
-}
+/// Batter heuristics that aren't in [`ALL_ALGORITHMS`], so webhooks that
+/// pick their jokes from every heuristic don't start getting them.
+pub const BATTER_ALGORITHMS: &[Builtin] = &[HITS, HOME_RUNS, STEALS];
@@ -0,0 +1,568 @@
+use super::{
+    Algorithm, Candidate, Category, Explanation, Forbidden, Metadata, Role, ScoredCandidate,
+};
+use anyhow::{anyhow, bail, ensure, Result};
+use idol_api::models::FeedEventType;
+use idol_api::team_pair::TeamPosition;
+use idol_api::{Dataset, State};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::CharIndices;
+
+pub struct Variable {
+    pub name: &'static str,
+    pub datasets: &'static [Dataset],
+    get: fn(Candidate<'_>) -> Option<f64>,
+}
+
+impl fmt::Debug for Variable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Variable").field(&self.name).finish()
+    }
+}
+
+macro_rules! variables {
+    ($($name:ident: [$($dataset:ident$(($kind:ident))?),*] |$x:ident| $get:expr,)*) => {
+        pub const VARIABLES: &[Variable] = &[
+            $(Variable {
+                name: stringify!($name),
+                datasets: &[$(Dataset::$dataset$((FeedEventType::$kind))?),*],
+                get: |$x| $get,
+            },)*
+        ];
+    };
+}
+
+macro_rules! attributes {
+    ($($name:ident),*) => {
+        variables! {
+            $($name: [] |x| x.player.$name.into(),)*
+            so9: [PitcherStats] |x| Some(x.pitching_stats?.strikeouts_per_9),
+            era: [PitcherStats] |x| x.pitching_stats?.earned_run_average,
+            whip: [PitcherStats] |x| x.pitching_stats?.walks_and_hits_per_inning,
+            so_bb: [PitcherStats] |x| x.pitching_stats?.strikeouts_per_walk,
+            innings: [PitcherStats] |x| Some(x.pitching_stats?.innings),
+            pitcher_games: [PitcherStats] |x| Some(x.pitching_stats?.games as f64),
+            pitcher_strikeouts: [PitcherStats] |x| Some(x.pitching_stats?.strikeouts as f64),
+            pitcher_walks: [PitcherStats] |x| Some(x.pitching_stats?.walks as f64),
+            hits_allowed: [PitcherStats] |x| Some(x.pitching_stats?.hits_allowed as f64),
+            batter_games: [BatterStats] |x| Some(x.batting_stats?.games as f64),
+            plate_appearances: [BatterStats] |x| Some(x.batting_stats?.plate_appearances as f64),
+            at_bats: [BatterStats] |x| Some(x.batting_stats?.at_bats as f64),
+            hits: [BatterStats] |x| Some(x.batting_stats?.hits as f64),
+            home_runs: [BatterStats] |x| Some(x.batting_stats?.home_runs as f64),
+            stolen_bases: [BatterStats] |x| Some(x.batting_stats?.stolen_bases as f64),
+            batter_strikeouts: [BatterStats] |x| Some(x.batting_stats?.strikeouts as f64),
+            batter_walks: [BatterStats] |x| Some(x.batting_stats?.walks as f64),
+            batting_average: [BatterStats] |x| x.batting_stats?.batting_average,
+            obp: [BatterStats] |x| x.batting_stats?.on_base_percentage,
+            opp_k_rate: [Strikeouts, AtBats] |x| x.opponent_strikeout_rate(),
+            opp_strikeouts: [Strikeouts] |x| x
+                .opponent
+                .strikeouts(x.state)
+                .map(|y| y.map(|z| z as f64))
+                .sum(),
+            opp_at_bats: [AtBats] |x| x
+                .opponent
+                .at_bats(x.state)
+                .map(|y| y.map(|z| z as f64))
+                .sum(),
+            odds: [] |x| Some(match x.team_pos {
+                TeamPosition::Home => x.game.home_odds,
+                TeamPosition::Away => x.game.away_odds,
+            }),
+            opp_odds: [] |x| Some(match x.team_pos {
+                TeamPosition::Home => x.game.away_odds,
+                TeamPosition::Away => x.game.home_odds,
+            }),
+            home: [] |x| Some(if x.team_pos == TeamPosition::Home { 1.0 } else { 0.0 }),
+            idol_rank: [Idols] |x| Some(
+                x.state
+                    .idols()
+                    .iter()
+                    .position(|y| y.player_id == x.id)
+                    .map_or(20.0, |y| y as f64 + 1.0)
+            ),
+            sun2_events: [Feed(Sun2Outcome)] |x| Some(
+                x.state
+                    .season_feed_events(FeedEventType::Sun2Outcome)
+                    .filter(|y| y.tags_team(x.team.id))
+                    .count() as f64
+            ),
+        }
+    };
+}
+
+attributes!(
+    ruthlessness,
+    patheticism,
+    overpowerment,
+    unthwackability,
+    shakespearianism,
+    suppression,
+    coldness,
+    thwackability,
+    buoyancy,
+    divinity,
+    moxie,
+    musclitude,
+    martyrdom,
+    tragicness,
+    base_thirst,
+    continuation,
+    ground_friction,
+    indulgence,
+    laserlikeness,
+    anticapitalism,
+    chasiness,
+    omniscience,
+    tenaciousness,
+    watchfulness,
+    pressurization,
+    cinnamon,
+    pitching_rating,
+    hitting_rating,
+    baserunning_rating,
+    defense_rating
+);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Function {
+    Abs,
+    Floor,
+    Ceil,
+    Sqrt,
+    Ln,
+    Min,
+    Max,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "abs" => Some(Self::Abs),
+            "floor" => Some(Self::Floor),
+            "ceil" => Some(Self::Ceil),
+            "sqrt" => Some(Self::Sqrt),
+            "ln" => Some(Self::Ln),
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            _ => None,
+        }
+    }
+
+    fn check_arity(self, name: &str, count: usize) -> Result<()> {
+        match self {
+            Self::Min | Self::Max => ensure!(count > 0, "{}() needs at least one argument", name),
+            _ => ensure!(count == 1, "{}() takes one argument, got {}", name, count),
+        }
+        Ok(())
+    }
+
+    fn apply(self, args: &[f64]) -> f64 {
+        match self {
+            Self::Abs => args[0].abs(),
+            Self::Floor => args[0].floor(),
+            Self::Ceil => args[0].ceil(),
+            Self::Sqrt => args[0].sqrt(),
+            Self::Ln => args[0].ln(),
+            Self::Min => args.iter().copied().fold(f64::INFINITY, f64::min),
+            Self::Max => args.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Number(f64),
+    Variable(usize),
+    Neg(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, values: &[Option<f64>]) -> Option<f64> {
+        Some(match self {
+            Self::Number(x) => *x,
+            Self::Variable(i) => values[*i]?,
+            Self::Neg(x) => -x.eval(values)?,
+            Self::Binary(op, a, b) => {
+                let (a, b) = (a.eval(values)?, b.eval(values)?);
+                match op {
+                    Op::Add => a + b,
+                    Op::Sub => a - b,
+                    Op::Mul => a * b,
+                    Op::Div => a / b,
+                    Op::Pow => a.powf(b),
+                }
+            }
+            Self::Call(func, args) => func.apply(
+                &args
+                    .iter()
+                    .map(|x| x.eval(values))
+                    .collect::<Option<Vec<_>>>()?,
+            ),
+        })
+    }
+}
+
+// Chained operators build a left-nested tree just like parentheses do, so both count
+// towards the limit that keeps evaluation from overflowing the stack.
+const MAX_DEPTH: usize = 64;
+
+struct Parser<'a> {
+    source: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    variables: Vec<&'static Variable>,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn nest(&mut self) -> Result<()> {
+        self.depth += 1;
+        ensure!(
+            self.depth <= MAX_DEPTH,
+            "{:?} has more than {} levels of nested or chained operators",
+            self.source,
+            MAX_DEPTH
+        );
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.chars.peek(), Some(&(_, c)) if c.is_whitespace()) {
+            self.chars.next();
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.peek().map(|x| x.1)
+    }
+
+    fn position(&mut self) -> usize {
+        self.chars.peek().map_or(self.source.len(), |x| x.0)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.chars.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            self.unexpected()
+        }
+    }
+
+    fn unexpected<T>(&mut self) -> Result<T> {
+        let position = self.position();
+        match self.peek() {
+            Some(c) => bail!("Unexpected {:?} at {} in {:?}", c, position, self.source),
+            None => bail!("Unexpected end of {:?}", self.source),
+        }
+    }
+
+    fn take_while(&mut self, mut pred: impl FnMut(char) -> bool) -> &'a str {
+        let start = self.position();
+        while matches!(self.chars.peek(), Some(&(_, c)) if pred(c)) {
+            self.chars.next();
+        }
+        &self.source[start..self.position()]
+    }
+
+    fn expr(&mut self) -> Result<Expr> {
+        let depth = self.depth;
+        let mut expr = self.term()?;
+        loop {
+            let op = if self.eat('+') {
+                Op::Add
+            } else if self.eat('-') {
+                Op::Sub
+            } else {
+                self.depth = depth;
+                return Ok(expr);
+            };
+            self.nest()?;
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr> {
+        let depth = self.depth;
+        let mut expr = self.unary()?;
+        loop {
+            let op = if self.eat('*') {
+                Op::Mul
+            } else if self.eat('/') {
+                Op::Div
+            } else {
+                self.depth = depth;
+                return Ok(expr);
+            };
+            self.nest()?;
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        let depth = self.depth;
+        self.nest()?;
+        let expr = if self.eat('-') {
+            Expr::Neg(Box::new(self.unary()?))
+        } else {
+            let base = self.atom()?;
+            if self.eat('^') {
+                Expr::Binary(Op::Pow, Box::new(base), Box::new(self.unary()?))
+            } else {
+                base
+            }
+        };
+        self.depth = depth;
+        Ok(expr)
+    }
+
+    fn atom(&mut self) -> Result<Expr> {
+        match self.peek() {
+            Some('(') => {
+                self.chars.next();
+                let expr = self.expr()?;
+                self.expect(')')?;
+                Ok(expr)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let position = self.position();
+                let number = self.take_while(|x| x.is_ascii_digit() || x == '.');
+                number.parse().map(Expr::Number).map_err(|_| {
+                    anyhow!(
+                        "Invalid number {:?} at {} in {:?}",
+                        number,
+                        position,
+                        self.source
+                    )
+                })
+            }
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                let name = self.take_while(|x| x.is_ascii_alphanumeric() || x == '_');
+                if self.eat('(') {
+                    let func = Function::from_name(name)
+                        .ok_or_else(|| anyhow!("Unknown function {:?}", name))?;
+                    let mut args = Vec::new();
+                    if !self.eat(')') {
+                        loop {
+                            args.push(self.expr()?);
+                            if self.eat(')') {
+                                break;
+                            }
+                            self.expect(',')?;
+                        }
+                    }
+                    func.check_arity(name, args.len())?;
+                    Ok(Expr::Call(func, args))
+                } else {
+                    self.variable(name)
+                }
+            }
+            _ => self.unexpected(),
+        }
+    }
+
+    fn variable(&mut self, name: &str) -> Result<Expr> {
+        let variable = VARIABLES
+            .iter()
+            .find(|x| x.name == name)
+            .ok_or_else(|| anyhow!("Unknown variable {:?}", name))?;
+        let index = match self.variables.iter().position(|x| x.name == name) {
+            Some(index) => index,
+            None => {
+                self.variables.push(variable);
+                self.variables.len() - 1
+            }
+        };
+        Ok(Expr::Variable(index))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Formula {
+    source: String,
+    expr: Expr,
+    variables: Vec<&'static Variable>,
+    datasets: Vec<Dataset>,
+}
+
+impl Formula {
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut parser = Parser {
+            source,
+            chars: source.char_indices().peekable(),
+            variables: Vec::new(),
+            depth: 0,
+        };
+        let expr = parser.expr()?;
+        if parser.peek().is_some() {
+            return parser.unexpected();
+        }
+        let mut datasets = parser
+            .variables
+            .iter()
+            .flat_map(|x| x.datasets.iter().copied())
+            .collect::<Vec<_>>();
+        datasets.sort();
+        datasets.dedup();
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+            variables: parser.variables,
+            datasets,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn variables(&self) -> &[&'static Variable] {
+        &self.variables
+    }
+
+    pub fn datasets(&self) -> &[Dataset] {
+        &self.datasets
+    }
+
+    pub fn explain(&self, candidate: Candidate<'_>) -> Option<Explanation> {
+        let values = self
+            .variables
+            .iter()
+            .map(|x| (x.get)(candidate))
+            .collect::<Vec<_>>();
+        let score = self.expr.eval(&values).filter(|x| x.is_finite())?;
+        Some(
+            self.variables
+                .iter()
+                .zip(values)
+                .fold(Explanation::new(score), |explanation, (x, value)| {
+                    explanation.with(x.name, value.unwrap_or(f64::NAN))
+                }),
+        )
+    }
+
+    pub fn eval(&self, candidate: Candidate<'_>) -> Option<f64> {
+        self.explain(candidate).map(|x| x.score)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FormulaConfig {
+    pub slug: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_category")]
+    pub category: Category,
+    #[serde(default = "default_role")]
+    pub role: Role,
+    #[serde(default)]
+    pub forbidden: bool,
+    pub formula: String,
+}
+
+fn default_category() -> Category {
+    Category::Joke
+}
+
+fn default_role() -> Role {
+    Role::Pitcher
+}
+
+pub struct FormulaAlgorithm {
+    metadata: Metadata,
+    formula: Formula,
+}
+
+impl FormulaAlgorithm {
+    pub fn new(config: FormulaConfig) -> Result<Self> {
+        ensure!(
+            !config.slug.is_empty()
+                && config
+                    .slug
+                    .chars()
+                    .all(|x| x.is_ascii_lowercase() || x.is_ascii_digit() || x == '_'),
+            "Invalid algorithm slug {:?}",
+            config.slug
+        );
+        let formula = Formula::parse(&config.formula)?;
+        Ok(Self {
+            metadata: Metadata {
+                slug: Cow::Owned(config.slug),
+                name: Cow::Owned(config.name),
+                description: Cow::Owned(config.description),
+                category: config.category,
+                role: config.role,
+                forbidden: if config.forbidden {
+                    Forbidden::Forbidden
+                } else {
+                    Forbidden::Unforbidden
+                },
+                printed_stats: Cow::Borrowed(&[]),
+                datasets: Cow::Owned(formula.datasets().to_vec()),
+            },
+            formula,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Vec<Self>> {
+        let file = BufReader::new(File::open(path)?);
+        let configs: Vec<FormulaConfig> = serde_json::from_reader(file)?;
+        configs
+            .into_iter()
+            .map(|config| {
+                let slug = config.slug.clone();
+                Self::new(config).map_err(|err| err.context(format!("Invalid algorithm {}", slug)))
+            })
+            .collect()
+    }
+
+    pub fn formula(&self) -> &Formula {
+        &self.formula
+    }
+}
+
+impl Algorithm for FormulaAlgorithm {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn best<'a>(&self, state: &'a State) -> Result<ScoredCandidate<'a>> {
+        ScoredCandidate::first(self.ranked(state)?, self.metadata.role)
+    }
+
+    fn ranked<'a>(&self, state: &'a State) -> Result<Vec<ScoredCandidate<'a>>> {
+        Ok(ScoredCandidate::ranked_explained(
+            state,
+            self.metadata.role,
+            |candidate| self.formula.explain(candidate),
+        ))
+    }
+}
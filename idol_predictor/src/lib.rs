@@ -1,20 +1,38 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, ensure, Result};
+use average::Mean;
 use either::Either;
-use idol_api::models::{Game, PitchingStats, Player, Position, Team};
+use idol_api::models::{BattingStats, Game, PitchingStats, Player, PlayerId, Position, Team};
 use idol_api::team_pair::{TeamPair, TeamPosition};
-use idol_api::State;
+use idol_api::{Dataset, State};
 use join_lazy_fmt::{lazy_format, Join};
 use noisy_float::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::cmp::Reverse;
 use std::fmt;
 
 pub mod algorithms;
+pub mod formula;
+pub mod registry;
+
+pub use formula::{Formula, FormulaAlgorithm, FormulaConfig};
+pub use registry::Registry;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Pitcher,
+    Batter,
+}
 
 #[derive(Debug, Copy, Clone)]
-pub struct PitcherRef<'a> {
-    pub id: &'a str,
+pub struct Candidate<'a> {
+    pub id: PlayerId,
     pub position: &'a Position,
     pub player: &'a Player,
-    pub stats: Option<&'a PitchingStats>,
+    pub role: Role,
+    pub pitching_stats: Option<&'a PitchingStats>,
+    pub batting_stats: Option<&'a BattingStats>,
     pub game: &'a Game,
     pub state: &'a State,
     pub team: &'a Team,
@@ -22,27 +40,100 @@ pub struct PitcherRef<'a> {
     pub team_pos: TeamPosition,
 }
 
-impl<'a> PitcherRef<'a> {
+impl<'a> Candidate<'a> {
+    pub fn new(
+        position: &'a Position,
+        role: Role,
+        game: &'a Game,
+        state: &'a State,
+        teams: TeamPair<&'a Team>,
+        team_pos: TeamPosition,
+    ) -> Self {
+        Candidate {
+            id: position.id,
+            position,
+            player: &position.data,
+            role,
+            pitching_stats: state.pitching_stats(position.id),
+            batting_stats: state.batting_stats(position.id),
+            game,
+            state,
+            team: teams[team_pos],
+            opponent: teams[team_pos.opposite()],
+            team_pos,
+        }
+    }
+
     pub fn pitchers(game: &'a Game, state: &'a State) -> Option<TeamPair<Self>> {
+        let teams = game.teams(state)?;
         Some(
             game.pitcher_positions(state)?
-                .zip(game.pitcher_stats(state))
-                .zip(game.teams(state)?)
-                .map_both_pos(
-                    |&((position, stats), team), &(_, opponent), team_pos| PitcherRef {
-                        id: &position.id,
-                        position,
-                        player: &position.data,
-                        stats,
-                        game,
-                        state,
-                        team,
-                        opponent,
-                        team_pos,
-                    },
-                ),
+                .map_pos(|position, team_pos| {
+                    Self::new(position, Role::Pitcher, game, state, teams, team_pos)
+                }),
+        )
+    }
+
+    pub fn batters(game: &'a Game, state: &'a State) -> impl Iterator<Item = Self> + 'a {
+        game.teams(state).into_iter().flat_map(move |teams| {
+            TeamPair::from_fn(|team_pos| team_pos)
+                .into_iter()
+                .flat_map(move |team_pos| {
+                    teams[team_pos].lineup.iter().filter_map(move |&id| {
+                        Some(Self::new(
+                            state.player(id)?,
+                            Role::Batter,
+                            game,
+                            state,
+                            teams,
+                            team_pos,
+                        ))
+                    })
+                })
+        })
+    }
+
+    pub fn all(
+        game: &'a Game,
+        state: &'a State,
+        role: Role,
+    ) -> Box<dyn Iterator<Item = Self> + 'a> {
+        match role {
+            Role::Pitcher => Box::new(Self::pitchers(game, state).into_iter().flatten()),
+            Role::Batter => Box::new(Self::batters(game, state)),
+        }
+    }
+
+    pub fn opponent_strikeout_rate(&self) -> Option<f64> {
+        Some(
+            self.opponent
+                .strikeouts(self.state)
+                .zip(self.opponent.at_bats(self.state))
+                .map(|(so, ab)| Some((so?, ab?)))
+                .map(|x| x.map(|(so, ab)| so as f64 / ab as f64))
+                .collect::<Option<Mean>>()?
+                .mean(),
         )
     }
+
+    pub fn for_player(position: &'a Position, state: &'a State, role: Role) -> Result<Self> {
+        let team_id = position
+            .data
+            .league_team_id
+            .ok_or_else(|| anyhow!("{} isn't on a team!", position.data.name))?;
+        let game = state
+            .games()
+            .iter()
+            .find(|x| x.team_ids().any(|y| y == team_id))
+            .ok_or_else(|| anyhow!("No game with {}!", position.data.name))?;
+        let teams = game
+            .teams(state)
+            .ok_or_else(|| anyhow!("Couldn't get teams!"))?;
+        let team_pos = teams
+            .position(|x| x.id == team_id)
+            .ok_or_else(|| anyhow!("{} isn't in their game!", position.data.name))?;
+        Ok(Self::new(position, role, game, state, teams, team_pos))
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -64,101 +155,374 @@ impl Forbidden {
 #[derive(Debug, Copy, Clone)]
 pub enum PrintedStat {
     SO9,
+    Hits,
+    HomeRuns,
+    StolenBases,
 }
 
 impl PrintedStat {
-    fn print(self, pitcher: PitcherRef) -> impl fmt::Display + '_ {
-        match (pitcher.stats, self) {
-            (Some(stats), Self::SO9) => {
-                Either::Left(lazy_format!("SO/9: {}", stats.strikeouts_per_9))
-            }
-            (None, Self::SO9) => Either::Right("SO/9: N/A"),
+    fn label(self) -> &'static str {
+        match self {
+            Self::SO9 => "SO/9",
+            Self::Hits => "H",
+            Self::HomeRuns => "HR",
+            Self::StolenBases => "SB",
+        }
+    }
+
+    fn value(self, candidate: Candidate<'_>) -> Option<f64> {
+        match self {
+            Self::SO9 => Some(candidate.pitching_stats?.strikeouts_per_9),
+            Self::Hits => Some(candidate.batting_stats?.hits as f64),
+            Self::HomeRuns => Some(candidate.batting_stats?.home_runs as f64),
+            Self::StolenBases => Some(candidate.batting_stats?.stolen_bases as f64),
+        }
+    }
+
+    fn print(self, candidate: Candidate<'_>) -> impl fmt::Display {
+        let label = self.label();
+        match self.value(candidate) {
+            Some(value) => Either::Left(lazy_format!("{}: {}", label, value)),
+            None => Either::Right(lazy_format!("{}: N/A", label)),
         }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-pub struct ScoredPitcher<'a> {
-    pub pitcher: PitcherRef<'a>,
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Term {
+    pub label: Cow<'static, str>,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Explanation {
+    pub score: f64,
+    pub terms: Vec<Term>,
+}
+
+impl Explanation {
+    pub fn new(score: f64) -> Self {
+        Self {
+            score,
+            terms: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, label: impl Into<Cow<'static, str>>, value: f64) -> Self {
+        self.terms.push(Term {
+            label: label.into(),
+            value,
+        });
+        self
+    }
+
+    fn print<'a>(&'a self, stats: &'a [PrintedStat]) -> impl fmt::Display + 'a {
+        "".join(
+            self.terms
+                .iter()
+                .filter(move |term| !stats.iter().any(|x| x.label() == term.label))
+                .map(|term| lazy_format!(", {}: {}", term.label, Value(term.value))),
+        )
+    }
+}
+
+struct Value(f64);
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.fract() == 0.0 {
+            write!(f, "{}", self.0)
+        } else {
+            write!(f, "{:.3}", self.0)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Recommendation {
+    pub algorithm: String,
+    pub name: String,
+    pub rank: usize,
+    pub player_id: PlayerId,
+    pub player: String,
+    pub role: Role,
+    pub team: String,
+    pub opponent: String,
+    pub team_pos: TeamPosition,
     pub score: f64,
+    pub explanation: Option<Explanation>,
 }
 
-impl<'a> ScoredPitcher<'a> {
-    pub fn best_pitcher(
+#[derive(Debug, Clone)]
+pub struct ScoredCandidate<'a> {
+    pub candidate: Candidate<'a>,
+    pub score: f64,
+    pub explanation: Option<Explanation>,
+}
+
+impl<'a> ScoredCandidate<'a> {
+    pub fn new(candidate: Candidate<'a>, score: f64) -> Self {
+        Self {
+            candidate,
+            score,
+            explanation: None,
+        }
+    }
+
+    pub fn ranked(
         state: &'a State,
-        mut strategy: impl FnMut(PitcherRef<'a>) -> Option<f64>,
-    ) -> Result<Self> {
-        state
-            .games
-            .iter()
-            .filter_map(|game| PitcherRef::pitchers(game, state))
-            .flatten()
-            .filter_map(|pitcher| {
-                Some(ScoredPitcher {
-                    pitcher,
-                    score: strategy(pitcher)?,
-                })
+        role: Role,
+        mut strategy: impl FnMut(Candidate<'a>) -> Option<f64>,
+    ) -> Vec<Self> {
+        Self::ranked_by(state, role, |candidate| {
+            Some(ScoredCandidate::new(candidate, strategy(candidate)?))
+        })
+    }
+
+    pub fn ranked_explained(
+        state: &'a State,
+        role: Role,
+        mut strategy: impl FnMut(Candidate<'a>) -> Option<Explanation>,
+    ) -> Vec<Self> {
+        Self::ranked_by(state, role, |candidate| {
+            let explanation = strategy(candidate)?;
+            Some(ScoredCandidate {
+                candidate,
+                score: explanation.score,
+                explanation: Some(explanation),
             })
-            .max_by_key(|scored| n64(scored.score))
-            .ok_or_else(|| anyhow!("No best pitcher!"))
+        })
     }
 
-    pub fn display<'b>(
-        &'b self,
-        strategy: &'b str,
-        forbidden: Forbidden,
-        stats: &'b [PrintedStat],
-    ) -> impl fmt::Display + 'b {
+    fn ranked_by(
+        state: &'a State,
+        role: Role,
+        strategy: impl FnMut(Candidate<'a>) -> Option<Self>,
+    ) -> Vec<Self> {
+        let ranked = state
+            .games()
+            .iter()
+            .flat_map(|game| Candidate::all(game, state, role))
+            .filter_map(strategy)
+            .collect();
+        Self::sorted(ranked)
+    }
+
+    pub fn sorted(mut ranked: Vec<Self>) -> Vec<Self> {
+        // NaN can't be ranked, and a pick scored infinitely says nothing about the player.
+        ranked.retain(|scored| scored.score.is_finite());
+        // Ties go to the last candidate, like max_by_key, so reverse before the stable sort.
+        ranked.reverse();
+        ranked.sort_by_key(|scored| Reverse(n64(scored.score)));
+        ranked
+    }
+
+    pub fn first(ranked: Vec<Self>, role: Role) -> Result<Self> {
+        ranked.into_iter().next().ok_or_else(|| match role {
+            Role::Pitcher => anyhow!("No best pitcher!"),
+            Role::Batter => anyhow!("No best batter!"),
+        })
+    }
+
+    pub fn best(
+        state: &'a State,
+        role: Role,
+        strategy: impl FnMut(Candidate<'a>) -> Option<f64>,
+    ) -> Result<Self> {
+        Self::first(Self::ranked(state, role, strategy), role)
+    }
+
+    pub fn recommendation(&self, metadata: &Metadata, rank: usize) -> Recommendation {
+        Recommendation {
+            algorithm: metadata.slug.to_string(),
+            name: metadata.name.to_string(),
+            rank,
+            player_id: self.candidate.id,
+            player: self.candidate.player.name.to_string(),
+            role: self.candidate.role,
+            team: self.candidate.team.full_name.to_string(),
+            opponent: self.candidate.opponent.full_name.to_string(),
+            team_pos: self.candidate.team_pos,
+            score: self.score,
+            explanation: self.explanation.clone(),
+        }
+    }
+
+    fn summary<'b>(&'b self, stats: &'b [PrintedStat]) -> impl fmt::Display + 'b {
         let printed_stats = "".join(
             stats
                 .iter()
-                .map(move |stat| lazy_format!(", {}", stat.print(self.pitcher))),
+                .map(move |stat| lazy_format!(", {}", stat.print(self.candidate))),
         );
-        let versus = match self.pitcher.team_pos {
+        let explanation = match &self.explanation {
+            Some(explanation) => Either::Left(explanation.print(stats)),
+            None => Either::Right(""),
+        };
+        let role = match self.candidate.role {
+            Role::Pitcher => "",
+            Role::Batter => "batting for ",
+        };
+        let versus = match self.candidate.team_pos {
             TeamPosition::Home => "vs.",
             TeamPosition::Away => "@",
         };
-        forbidden.forbid(lazy_format!(
-            "{strategy}: {name} ({score:.3}{stats}, **{team}** {versus} {opponent})",
-            strategy = strategy,
-            name = self.pitcher.player.name,
+        lazy_format!(
+            "{name} ({score:.3}{stats}{explanation}, {role}**{team}** {versus} {opponent})",
+            name = self.candidate.player.name,
             stats = printed_stats,
+            explanation = explanation,
             score = self.score,
-            team = self.pitcher.team.full_name,
+            role = role,
+            team = self.candidate.team.full_name,
             versus = versus,
-            opponent = self.pitcher.opponent.full_name
-        ))
+            opponent = self.candidate.opponent.full_name
+        )
+    }
+
+    pub fn display<'b>(
+        &'b self,
+        strategy: &'b str,
+        forbidden: Forbidden,
+        stats: &'b [PrintedStat],
+    ) -> impl fmt::Display + 'b {
+        forbidden.forbid(lazy_format!("{}: {}", strategy, self.summary(stats)))
+    }
+
+    pub fn display_ranking<'b>(
+        ranked: &'b [Self],
+        strategy: &'b str,
+        forbidden: Forbidden,
+        stats: &'b [PrintedStat],
+    ) -> impl fmt::Display + 'b {
+        let leader = ranked.first().map_or(0.0, |x| x.score);
+        let rows = "\n".join(ranked.iter().enumerate().map(move |(i, scored)| {
+            let behind = if i == 0 {
+                Either::Left("")
+            } else {
+                Either::Right(lazy_format!(", {:.3} behind", leader - scored.score))
+            };
+            forbidden.forbid(lazy_format!(
+                "{}. {}{}",
+                i + 1,
+                scored.summary(stats),
+                behind
+            ))
+        }));
+        lazy_format!("{}:\n{}", strategy, rows)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Serious,
+    Joke,
+}
+
+#[derive(Debug, Clone)]
+pub struct Metadata {
+    pub slug: Cow<'static, str>,
+    pub name: Cow<'static, str>,
+    pub description: Cow<'static, str>,
+    pub category: Category,
+    pub role: Role,
+    pub forbidden: Forbidden,
+    pub printed_stats: Cow<'static, [PrintedStat]>,
+    pub datasets: Cow<'static, [Dataset]>,
+}
+
+impl Metadata {
+    pub fn missing_dataset(&self, state: &State) -> Option<Dataset> {
+        self.datasets
+            .iter()
+            .copied()
+            .find(|x| state.missing().contains(x))
+    }
+
+    pub fn display<'a>(&'a self, scored: &'a ScoredCandidate<'_>) -> impl fmt::Display + 'a {
+        scored.display(&self.name, self.forbidden, &self.printed_stats)
+    }
+
+    pub fn display_ranking<'a>(
+        &'a self,
+        ranked: &'a [ScoredCandidate<'_>],
+    ) -> impl fmt::Display + 'a {
+        ScoredCandidate::display_ranking(ranked, &self.name, self.forbidden, &self.printed_stats)
+    }
+}
+
+pub trait Algorithm: Send + Sync {
+    fn metadata(&self) -> &Metadata;
+
+    fn best<'a>(&self, state: &'a State) -> Result<ScoredCandidate<'a>>;
+
+    fn ranked<'a>(&self, state: &'a State) -> Result<Vec<ScoredCandidate<'a>>> {
+        Ok(vec![self.best(state)?])
+    }
+
+    fn top<'a>(&self, state: &'a State, count: usize) -> Result<Vec<ScoredCandidate<'a>>> {
+        let mut ranked = self.ranked(state)?;
+        ranked.truncate(count);
+        Ok(ranked)
+    }
+
+    fn write_best_to(&self, state: &State, output: &mut dyn fmt::Write) -> Result<()> {
+        writeln!(output, "{}", self.metadata().display(&self.best(state)?))?;
+        Ok(())
+    }
+
+    fn write_top_to(&self, state: &State, count: usize, output: &mut dyn fmt::Write) -> Result<()> {
+        let top = self.top(state, count)?;
+        ensure!(
+            !top.is_empty(),
+            "No candidates for {}!",
+            self.metadata().name
+        );
+        writeln!(output, "{}", self.metadata().display_ranking(&top))?;
+        Ok(())
     }
 }
 
 #[derive(Copy, Clone)]
 pub enum Strategy {
-    Maximize(fn(PitcherRef) -> Option<f64>),
-    Custom(fn(&State) -> Result<ScoredPitcher>),
+    Maximize(fn(Candidate<'_>) -> Option<f64>),
+    Explain(fn(Candidate<'_>) -> Option<Explanation>),
+    Custom(fn(&State) -> Result<Vec<ScoredCandidate<'_>>>),
 }
 
-#[derive(Copy, Clone)]
-pub struct Algorithm {
-    pub name: &'static str,
-    pub forbidden: Forbidden,
-    pub printed_stats: &'static [PrintedStat],
+#[derive(Clone)]
+pub struct Builtin {
+    pub metadata: Metadata,
     pub strategy: Strategy,
 }
 
-impl Algorithm {
-    pub fn best_pitcher(self, state: &State) -> Result<ScoredPitcher> {
-        match self.strategy {
-            Strategy::Maximize(score) => ScoredPitcher::best_pitcher(state, score),
-            Strategy::Custom(strat) => strat(state),
-        }
+impl Algorithm for Builtin {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
     }
 
-    pub fn display<'a, 'b>(self, scored: &'a ScoredPitcher<'b>) -> impl fmt::Display + 'a {
-        scored.display(self.name, self.forbidden, self.printed_stats)
+    fn best<'a>(&self, state: &'a State) -> Result<ScoredCandidate<'a>> {
+        match self.strategy {
+            Strategy::Maximize(score) => ScoredCandidate::best(state, self.metadata.role, score),
+            Strategy::Explain(explain) => ScoredCandidate::first(
+                ScoredCandidate::ranked_explained(state, self.metadata.role, explain),
+                self.metadata.role,
+            ),
+            Strategy::Custom(strat) => ScoredCandidate::first(strat(state)?, self.metadata.role),
+        }
     }
 
-    pub fn write_best_to(self, state: &State, output: &mut impl fmt::Write) -> Result<()> {
-        writeln!(output, "{}", self.display(&self.best_pitcher(state)?))?;
-        Ok(())
+    fn ranked<'a>(&self, state: &'a State) -> Result<Vec<ScoredCandidate<'a>>> {
+        match self.strategy {
+            Strategy::Maximize(score) => {
+                Ok(ScoredCandidate::ranked(state, self.metadata.role, score))
+            }
+            Strategy::Explain(explain) => Ok(ScoredCandidate::ranked_explained(
+                state,
+                self.metadata.role,
+                explain,
+            )),
+            Strategy::Custom(strat) => strat(state),
+        }
     }
 }
@@ -0,0 +1,77 @@
+use super::algorithms::{ALL_ALGORITHMS, BATTER_ALGORITHMS};
+use super::{Algorithm, Category, FormulaAlgorithm};
+use anyhow::{ensure, Result};
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Clone, Default)]
+pub struct Registry {
+    algorithms: Vec<Arc<dyn Algorithm>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn builtin() -> Self {
+        Self {
+            algorithms: ALL_ALGORITHMS
+                .iter()
+                .cloned()
+                .map(|x| Arc::new(x) as Arc<dyn Algorithm>)
+                .collect(),
+        }
+    }
+
+    pub fn register(&mut self, algorithm: impl Algorithm + 'static) -> Result<()> {
+        let slug = &algorithm.metadata().slug;
+        ensure!(
+            self.get(slug).is_none(),
+            "Algorithm {:?} is already registered",
+            slug
+        );
+        self.algorithms.push(Arc::new(algorithm));
+        Ok(())
+    }
+
+    pub fn with(mut self, algorithm: impl Algorithm + 'static) -> Result<Self> {
+        self.register(algorithm)?;
+        Ok(self)
+    }
+
+    pub fn register_batters(&mut self) -> Result<()> {
+        for algorithm in BATTER_ALGORITHMS {
+            self.register(algorithm.clone())?;
+        }
+        Ok(())
+    }
+
+    pub fn load_formulas(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        for algorithm in FormulaAlgorithm::load(path)? {
+            self.register(algorithm)?;
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, slug: &str) -> Option<&dyn Algorithm> {
+        self.iter().find(|x| x.metadata().slug == slug)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Algorithm> {
+        self.algorithms.iter().map(|x| &**x)
+    }
+
+    pub fn category(&self, category: Category) -> impl Iterator<Item = &dyn Algorithm> {
+        self.iter()
+            .filter(move |x| x.metadata().category == category)
+    }
+
+    pub fn len(&self) -> usize {
+        self.algorithms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.algorithms.is_empty()
+    }
+}
@@ -0,0 +1,166 @@
+use anyhow::Result;
+use idol_api::models::TeamId;
+use idol_api::{State, StateOptions};
+use idol_predictor::{algorithms, Algorithm, Candidate, Role, ScoredCandidate};
+use idol_test_support::Fixtures;
+
+#[async_std::test]
+async fn resolves_player_context() -> Result<()> {
+    let state = Fixtures::sample_state().await?;
+    let position = state
+        .players()
+        .iter()
+        .find(|x| x.data.name == "Crab Batter One")
+        .unwrap();
+    let candidate = Candidate::for_player(position, &state, Role::Batter)?;
+    assert_eq!(candidate.team.full_name, "Baltimore Crabs");
+    assert_eq!(candidate.opponent.full_name, "Tokyo Lift");
+    assert_eq!(candidate.batting_stats.map(|x| x.hits), Some(11));
+    Ok(())
+}
+
+#[async_std::test]
+async fn renders_batter_picks() -> Result<()> {
+    let state = Fixtures::sample_state().await?;
+    let best = algorithms::HITS.best(&state)?;
+    assert_eq!(best.candidate.role, Role::Batter);
+    let text = algorithms::HITS.metadata.display(&best).to_string();
+    assert!(text.starts_with(
+        "Best by hits: Crab Batter One (11.000, H: 11, batting for **Baltimore Crabs**"
+    ));
+    Ok(())
+}
+
+#[async_std::test]
+async fn renders_pitcher_picks() -> Result<()> {
+    let state = Fixtures::sample_state().await?;
+    let best = algorithms::SO9.best(&state)?;
+    assert_eq!(best.candidate.role, Role::Pitcher);
+    assert!(!algorithms::SO9
+        .metadata
+        .display(&best)
+        .to_string()
+        .contains("batting for"));
+    Ok(())
+}
+
+#[async_std::test]
+async fn ranks_all_candidates() -> Result<()> {
+    let state = Fixtures::sample_state().await?;
+    let ranked = algorithms::SO9.ranked(&state)?;
+    assert_eq!(ranked.len(), 2);
+    assert!(ranked[0].score >= ranked[1].score);
+    assert_eq!(
+        ranked[0].candidate.id,
+        algorithms::SO9.best(&state)?.candidate.id
+    );
+    assert_eq!(algorithms::SO9.top(&state, 1)?.len(), 1);
+
+    let table = algorithms::SO9
+        .metadata
+        .display_ranking(&ranked)
+        .to_string();
+    let lines = table.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "Best by SO/9:");
+    assert!(lines[1].starts_with("1. "));
+    assert!(lines[2].starts_with("2. ") && lines[2].ends_with(" behind"));
+    Ok(())
+}
+
+#[async_std::test]
+async fn explains_scores() -> Result<()> {
+    let state = Fixtures::sample_state().await?;
+    let best = algorithms::STAT_RATIO.best(&state)?;
+    let explanation = best.explanation.clone().unwrap();
+    assert_eq!(explanation.score, best.score);
+    let labels = explanation
+        .terms
+        .iter()
+        .map(|x| x.label.as_ref())
+        .collect::<Vec<_>>();
+    assert_eq!(labels, ["SO/9", "opponent SO/AB"]);
+
+    let text = algorithms::STAT_RATIO.metadata.display(&best).to_string();
+    assert_eq!(text.matches(", SO/9: ").count(), 1);
+    assert!(text.contains(", opponent SO/AB: "));
+
+    let json = serde_json::to_value(best.recommendation(&algorithms::STAT_RATIO.metadata, 1))?;
+    assert_eq!(json["algorithm"], "stat_ratio");
+    assert_eq!(json["explanation"]["terms"][0]["label"], "SO/9");
+    Ok(())
+}
+
+#[async_std::test]
+async fn falls_back_to_black_hole_without_a_game() -> Result<()> {
+    let fixtures = Fixtures::sample();
+    let options = StateOptions::default();
+    let state = State::from_games_and_season(Vec::new(), 11, &fixtures.data, &options).await?;
+    let best = algorithms::BEST_BEST.best(&state)?;
+    assert_eq!(best.candidate.player.name, "Wyatt Best");
+    assert_eq!(best.candidate.opponent.full_name, "Black Hole");
+
+    let crabs = TeamId::from_u128(0x8d87c468_699a_47a8_b40d_cfb73a5660ad);
+    let mut games = fixtures.events[0].upcoming_games();
+    for game in &mut games {
+        if game.home_team == crabs {
+            game.away_team = TeamId::nil();
+        } else if game.away_team == crabs {
+            game.home_team = TeamId::nil();
+        }
+    }
+    let state = State::from_games_and_season(games, 11, &fixtures.data, &options).await?;
+    let err = algorithms::BEST_BEST.best(&state).unwrap_err();
+    assert!(err.to_string().contains("Couldn't get teams"), "{}", err);
+    Ok(())
+}
+
+#[async_std::test]
+async fn ranks_custom_candidates() -> Result<()> {
+    let mut fixtures = Fixtures::sample();
+    for player in &mut fixtures.data.players {
+        if player.data.name == "Lift Batter Two" {
+            player.data.name = "Lift Best".into();
+        }
+    }
+    let state = State::from_event(
+        &fixtures.events[0],
+        &fixtures.data,
+        &StateOptions::default(),
+    )
+    .await?;
+    let ranked = algorithms::BESTNESS.ranked(&state)?;
+    let names = ranked
+        .iter()
+        .map(|x| &x.candidate.player.name[..])
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Lift Best", "Wyatt Best"]);
+    assert_eq!(
+        algorithms::BESTNESS.best(&state)?.candidate.player.name,
+        "Lift Best"
+    );
+    Ok(())
+}
+
+#[async_std::test]
+async fn skips_non_finite_scores() -> Result<()> {
+    let state = Fixtures::sample_state().await?;
+    let mut ranked = algorithms::SO9.ranked(&state)?;
+    let id = ranked[0].candidate.id;
+    ranked[0].score = f64::NAN;
+    let sorted = ScoredCandidate::sorted(ranked);
+    assert_eq!(sorted.len(), 1);
+    assert_ne!(sorted[0].candidate.id, id);
+
+    let mut fixtures = Fixtures::sample();
+    for stats in &mut fixtures.data.pitcher_stats {
+        stats.games = 0;
+    }
+    let state = State::from_event(
+        &fixtures.events[0],
+        &fixtures.data,
+        &StateOptions::default(),
+    )
+    .await?;
+    assert!(algorithms::GAMES_PER_GAME.ranked(&state)?.is_empty());
+    Ok(())
+}
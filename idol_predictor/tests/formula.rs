@@ -0,0 +1,110 @@
+use anyhow::Result;
+use idol_api::models::FeedEventType;
+use idol_api::Dataset;
+use idol_predictor::{algorithms, Algorithm, Formula, FormulaAlgorithm, FormulaConfig, Registry};
+use idol_test_support::Fixtures;
+
+#[test]
+fn parses_formulas() -> Result<()> {
+    let formula = Formula::parse("so9 * (0.2 + opp_k_rate) - max(era, 1) ^ 2")?;
+    let names = formula
+        .variables()
+        .iter()
+        .map(|x| x.name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["so9", "opp_k_rate", "era"]);
+    assert_eq!(
+        formula.datasets(),
+        [Dataset::Strikeouts, Dataset::AtBats, Dataset::PitcherStats]
+    );
+    assert_eq!(
+        Formula::parse("sun2_events")?.datasets(),
+        [Dataset::Feed(FeedEventType::Sun2Outcome)]
+    );
+
+    for (source, error) in [
+        ("so9 +", "Unexpected end"),
+        ("so9 * (1", "Unexpected end"),
+        ("so9 so9", "Unexpected 's' at 4"),
+        ("strikeouts_per_nine", "Unknown variable"),
+        ("exp(so9)", "Unknown function"),
+        ("abs(so9, era)", "abs() takes one argument"),
+        ("1..2", "Invalid number"),
+    ]
+    .iter()
+    {
+        let err = Formula::parse(source).unwrap_err().to_string();
+        assert!(err.contains(error), "{:?}: {}", source, err);
+    }
+    Ok(())
+}
+
+#[test]
+fn limits_nesting() -> Result<()> {
+    Formula::parse(&format!("{}so9{}", "(".repeat(20), ")".repeat(20)))?;
+    Formula::parse(&("so9".to_string() + &"+1".repeat(60)))?;
+    for source in [
+        format!("{}so9{}", "(".repeat(100), ")".repeat(100)),
+        "-".repeat(100) + "so9",
+        "so9".to_string() + &"+1".repeat(64),
+        "so9".to_string() + &"^2".repeat(100),
+    ]
+    .iter()
+    {
+        let err = Formula::parse(source).unwrap_err().to_string();
+        assert!(
+            err.contains("levels of nested or chained operators"),
+            "{}",
+            err
+        );
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn evaluates_formulas() -> Result<()> {
+    let state = Fixtures::sample_state().await?;
+    let config: FormulaConfig = serde_json::from_str(
+        r#"{"slug": "so9_again", "name": "Best by SO9, again", "formula": "so9"}"#,
+    )?;
+    let algorithm = FormulaAlgorithm::new(config)?;
+    assert_eq!(algorithm.metadata().datasets[..], [Dataset::PitcherStats]);
+
+    let best = algorithm.best(&state)?;
+    let builtin = algorithms::SO9.best(&state)?;
+    assert_eq!(best.candidate.id, builtin.candidate.id);
+    assert!((best.score - builtin.score).abs() < 1e-9);
+    let terms = &best.explanation.as_ref().unwrap().terms;
+    assert_eq!(terms.len(), 1);
+    assert_eq!(terms[0].label, "so9");
+
+    let batters: FormulaConfig = serde_json::from_str(
+        r#"{"slug": "hits_again", "name": "Best by hits, again", "role": "batter", "formula": "hits + home"}"#,
+    )?;
+    let best = FormulaAlgorithm::new(batters)?.best(&state)?;
+    assert_eq!(best.candidate.player.name, "Crab Batter One");
+    Ok(())
+}
+
+#[test]
+fn loads_formula_files() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("idol_formulas_{}.json", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"[{"slug": "lift_odds", "name": "Best by odds", "category": "serious", "formula": "odds"},
+            {"slug": "so9", "name": "Duplicate", "formula": "so9"}]"#,
+    )?;
+    let mut registry = Registry::builtin();
+    let err = registry.load_formulas(&path).unwrap_err();
+    assert!(err.to_string().contains("already registered"));
+    assert!(registry.get("lift_odds").is_some());
+
+    std::fs::write(
+        &path,
+        r#"[{"slug": "Bad Slug", "name": "x", "formula": "1"}]"#,
+    )?;
+    let err = Registry::new().load_formulas(&path).unwrap_err();
+    assert!(format!("{:#}", err).contains("Invalid algorithm slug"));
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
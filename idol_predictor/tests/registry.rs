@@ -0,0 +1,89 @@
+use anyhow::Result;
+use idol_api::State;
+use idol_predictor::{
+    algorithms, Algorithm, Candidate, Category, Forbidden, Metadata, Registry, Role,
+    ScoredCandidate,
+};
+use idol_test_support::Fixtures;
+use std::borrow::Cow;
+
+struct Named {
+    metadata: Metadata,
+    player: String,
+}
+
+impl Named {
+    fn new(player: &str) -> Self {
+        Self {
+            metadata: Metadata {
+                slug: Cow::Owned(format!("named_{}", player.to_lowercase())),
+                name: Cow::Owned(format!("Just {}", player)),
+                description: Cow::Borrowed("Always picks the same player."),
+                category: Category::Joke,
+                role: Role::Pitcher,
+                forbidden: Forbidden::Unforbidden,
+                printed_stats: Cow::Borrowed(&[]),
+                datasets: Cow::Borrowed(&[]),
+            },
+            player: player.to_string(),
+        }
+    }
+}
+
+impl Algorithm for Named {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn best<'a>(&self, state: &'a State) -> Result<ScoredCandidate<'a>> {
+        let position = state
+            .players()
+            .iter()
+            .find(|x| x.data.name.contains(&self.player))
+            .ok_or_else(|| anyhow::anyhow!("No {}!", self.player))?;
+        Ok(ScoredCandidate::new(
+            Candidate::for_player(position, state, Role::Pitcher)?,
+            1.0,
+        ))
+    }
+}
+
+#[test]
+fn lists_builtin_algorithms_by_category() {
+    let registry = Registry::builtin();
+    assert_eq!(registry.len(), algorithms::ALL_ALGORITHMS.len());
+    let serious = registry
+        .category(Category::Serious)
+        .map(|x| x.metadata().slug.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(serious, ["so9", "ruthlessness", "stat_ratio"]);
+    assert!(registry.get("best_best").is_some());
+    assert!(registry.get("nonexistent").is_none());
+    assert!(registry.get("hits").is_none());
+}
+
+#[test]
+fn registers_batter_algorithms_on_request() -> Result<()> {
+    let mut registry = Registry::builtin();
+    registry.register_batters()?;
+    assert_eq!(
+        registry.len(),
+        algorithms::ALL_ALGORITHMS.len() + algorithms::BATTER_ALGORITHMS.len()
+    );
+    assert!(registry.get("hits").is_some());
+    assert!(registry.register_batters().is_err());
+    Ok(())
+}
+
+#[async_std::test]
+async fn runs_registered_algorithms() -> Result<()> {
+    let state = Fixtures::sample_state().await?;
+    let mut registry = Registry::builtin().with(Named::new("Sutton"))?;
+    assert!(registry.register(Named::new("Sutton")).is_err());
+
+    let algorithm = registry.get("named_sutton").unwrap();
+    let mut text = String::new();
+    algorithm.write_best_to(&state, &mut text)?;
+    assert!(text.starts_with("Just Sutton: Sutton Game (1.000, **Tokyo Lift**"));
+    Ok(())
+}
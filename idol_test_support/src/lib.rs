@@ -0,0 +1,425 @@
+use anyhow::Result;
+use idol_api::models::{Event, FeedEventType, PlayerId};
+use idol_api::provider::{DataProvider, FixtureProvider};
+use idol_api::{DataSources, Dataset, State, StateOptions};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tide::listener::{Listener, ToListener};
+use tide::utils::async_trait;
+use tide::{Body, Middleware, Next, Request, Response, StatusCode};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Fixtures {
+    pub events: Vec<Event>,
+    pub data: FixtureProvider,
+    pub versions: HashMap<String, Vec<serde_json::Value>>,
+    pub forbidden: Vec<PlayerId>,
+}
+
+#[derive(Deserialize)]
+struct SampleFile {
+    event: Event,
+    data: FixtureProvider,
+}
+
+impl Fixtures {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let file = std::fs::File::open(path)?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
+
+    pub fn sample() -> Self {
+        let sample: SampleFile =
+            serde_json::from_str(include_str!("../fixtures/sample.json")).unwrap();
+        Self {
+            events: vec![sample.event],
+            data: sample.data,
+            versions: HashMap::new(),
+            forbidden: Vec::new(),
+        }
+    }
+
+    pub async fn sample_state() -> Result<State> {
+        let fixtures = Self::sample();
+        Ok(State::from_event(
+            &fixtures.events[0],
+            &fixtures.data,
+            &StateOptions::default(),
+        )
+        .await?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WebhookMessage {
+    pub hook: String,
+    pub content: String,
+}
+
+#[derive(Clone)]
+struct MockState {
+    fixtures: Arc<Fixtures>,
+    webhooks: Arc<Mutex<Vec<WebhookMessage>>>,
+    hits: Arc<Mutex<HashMap<String, Vec<u16>>>>,
+}
+
+type MockRequest = Request<MockState>;
+
+pub struct MockServer {
+    url: String,
+    webhooks: Arc<Mutex<Vec<WebhookMessage>>>,
+    hits: Arc<Mutex<HashMap<String, Vec<u16>>>>,
+}
+
+fn json(res: Result<impl Serialize>) -> tide::Result {
+    match res {
+        Ok(x) => Ok(Response::builder(StatusCode::Ok)
+            .body(Body::from_json(&x)?)
+            .build()),
+        Err(err) => Ok(Response::builder(StatusCode::InternalServerError)
+            .body(err.to_string())
+            .build()),
+    }
+}
+
+#[derive(Deserialize)]
+struct LeadersQuery {
+    stat: String,
+    season: isize,
+}
+
+async fn season_leaders(req: MockRequest) -> tide::Result {
+    let query: LeadersQuery = req.query()?;
+    let data = &req.state().fixtures.data;
+    match query.stat.as_str() {
+        "strikeouts" => json(data.strikeout_leaders(query.season).await),
+        "at_bats" => json(data.at_bat_leaders(query.season).await),
+        _ => Ok(Response::new(StatusCode::NotFound)),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StatsQuery {
+    category: String,
+    player_ids: String,
+    season: isize,
+}
+
+async fn player_stats(req: MockRequest) -> tide::Result {
+    let query: StatsQuery = req.query()?;
+    let ids = query
+        .player_ids
+        .split(',')
+        .filter_map(|x| x.parse().ok())
+        .collect::<Vec<PlayerId>>();
+    let data = &req.state().fixtures.data;
+    match query.category.as_str() {
+        "pitching" => json(data.pitcher_stats(&ids, query.season).await),
+        "batting" => json(data.batter_stats(&ids, query.season).await),
+        _ => Ok(Response::new(StatusCode::NotFound)),
+    }
+}
+
+#[derive(Deserialize)]
+struct FeedQuery {
+    #[serde(rename = "type")]
+    kind: FeedEventType,
+    limit: Option<usize>,
+}
+
+async fn feed(req: MockRequest) -> tide::Result {
+    let query: FeedQuery = req.query()?;
+    json(
+        req.state()
+            .fixtures
+            .data
+            .feed_events(query.kind, query.limit)
+            .await,
+    )
+}
+
+async fn idols(req: MockRequest) -> tide::Result {
+    #[derive(Serialize)]
+    struct Idols<T> {
+        idols: T,
+    }
+
+    let idols = req.state().fixtures.data.idols().await;
+    json(idols.map(|idols| Idols { idols }))
+}
+
+const ENTITY_PAGE_SIZE: usize = 4;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Page<'a> {
+    next_page: Option<String>,
+    items: &'a [serde_json::Value],
+}
+
+fn page(items: &[serde_json::Value], page: Option<usize>, count: usize) -> Page<'_> {
+    let start = page.unwrap_or(0).min(items.len());
+    let end = (start + count).min(items.len());
+    Page {
+        next_page: if end < items.len() {
+            Some(end.to_string())
+        } else {
+            None
+        },
+        items: &items[start..end],
+    }
+}
+
+fn entity(id: impl Serialize, data: impl Serialize) -> serde_json::Value {
+    serde_json::json!({
+        "entityId": id,
+        "validFrom": "2020-07-20T00:00:00Z",
+        "validTo": null,
+        "data": data,
+    })
+}
+
+#[derive(Deserialize)]
+struct EntitiesQuery {
+    #[serde(rename = "type")]
+    kind: String,
+    page: Option<usize>,
+}
+
+async fn entities(req: MockRequest) -> tide::Result {
+    let query: EntitiesQuery = req.query()?;
+    let fixtures = &req.state().fixtures;
+    let items = match query.kind.as_str() {
+        "player" => match fixtures.data.players().await {
+            Ok(players) => players.iter().map(|x| entity(x.id, &x.data)).collect(),
+            Err(err) => return json(Err::<(), _>(err)),
+        },
+        kind => fixtures.versions.get(kind).cloned().unwrap_or_default(),
+    };
+    json(Ok(page(&items, query.page, ENTITY_PAGE_SIZE)))
+}
+
+#[derive(Deserialize)]
+struct PlayersQuery {
+    forbidden: Option<bool>,
+    page: Option<usize>,
+}
+
+async fn players(req: MockRequest) -> tide::Result {
+    let query: PlayersQuery = req.query()?;
+    let fixtures = &req.state().fixtures;
+    let players = match fixtures.data.players().await {
+        Ok(players) => players,
+        Err(err) => return json(Err::<(), _>(err)),
+    };
+    let items = players
+        .iter()
+        .filter(|x| match query.forbidden {
+            Some(forbidden) => fixtures.forbidden.contains(&x.id) == forbidden,
+            None => true,
+        })
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()?;
+    json(Ok(page(&items, query.page, ENTITY_PAGE_SIZE)))
+}
+
+#[derive(Deserialize)]
+struct GamesQuery {
+    season: isize,
+    page: Option<usize>,
+}
+
+async fn games(req: MockRequest) -> tide::Result {
+    let query: GamesQuery = req.query()?;
+    let data = &req.state().fixtures.data;
+    if data.missing.contains(&Dataset::PastGames) {
+        return json(Err::<(), _>(anyhow::anyhow!("Missing past games")));
+    }
+    let items = data
+        .past_games
+        .iter()
+        .filter(|x| x.data.season == query.season)
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()?;
+    json(Ok(page(&items, query.page, ENTITY_PAGE_SIZE)))
+}
+
+#[derive(Deserialize)]
+struct VersionsQuery {
+    #[serde(rename = "type")]
+    kind: String,
+    count: Option<usize>,
+    page: Option<usize>,
+}
+
+async fn versions(req: MockRequest) -> tide::Result {
+    let query: VersionsQuery = req.query()?;
+    let versions = req
+        .state()
+        .fixtures
+        .versions
+        .get(&query.kind)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    json(Ok(page(
+        versions,
+        query.page,
+        query.count.unwrap_or(versions.len()),
+    )))
+}
+
+struct Conditional;
+
+#[async_trait]
+impl Middleware<MockState> for Conditional {
+    async fn handle(&self, req: MockRequest, next: Next<'_, MockState>) -> tide::Result {
+        let path = req.url().path().to_string();
+        let if_none_match = req.header("If-None-Match").map(|x| x.as_str().to_string());
+        let hits = req.state().hits.clone();
+
+        let mut res = next.run(req).await;
+        if res.status() == StatusCode::Ok {
+            let body = res.take_body().into_bytes().await?;
+            let mut hasher = DefaultHasher::new();
+            body.hash(&mut hasher);
+            let etag = format!("\"{:x}\"", hasher.finish());
+            if if_none_match.as_deref() == Some(etag.as_str()) {
+                res = Response::new(StatusCode::NotModified);
+            } else {
+                let mime = res.content_type();
+                res.set_body(body);
+                if let Some(mime) = mime {
+                    res.set_content_type(mime);
+                }
+            }
+            res.insert_header("ETag", etag);
+        }
+        hits.lock()
+            .unwrap()
+            .entry(path)
+            .or_default()
+            .push(res.status() as u16);
+        Ok(res)
+    }
+}
+
+async fn webhook(mut req: MockRequest) -> tide::Result {
+    #[derive(Deserialize)]
+    struct Payload {
+        content: String,
+    }
+
+    let hook = req.param("hook")?.to_string();
+    if hook == "gone" {
+        return Ok(Response::new(StatusCode::NotFound));
+    }
+    let payload: Payload = req.body_json().await?;
+    req.state().webhooks.lock().unwrap().push(WebhookMessage {
+        hook,
+        content: payload.content,
+    });
+    Ok(Response::new(StatusCode::NoContent))
+}
+
+impl MockServer {
+    pub async fn start(fixtures: Fixtures) -> Result<Self> {
+        let webhooks = Arc::new(Mutex::new(Vec::new()));
+        let hits = Arc::new(Mutex::new(HashMap::new()));
+        let mut app = tide::with_state(MockState {
+            fixtures: Arc::new(fixtures),
+            webhooks: webhooks.clone(),
+            hits: hits.clone(),
+        });
+
+        app.at("/blaseball/database/allTeams")
+            .with(Conditional)
+            .get(|req: MockRequest| async move { json(req.state().fixtures.data.teams().await) });
+        app.at("/blaseball/database/feed/global")
+            .with(Conditional)
+            .get(feed);
+        app.at("/blaseball/api/getIdols")
+            .with(Conditional)
+            .get(idols);
+        app.at("/blaseball/events/streamData")
+            .get(tide::sse::endpoint(|req: MockRequest, sender| async move {
+                for event in &req.state().fixtures.events {
+                    sender
+                        .send("message", serde_json::to_string(event)?, None)
+                        .await?;
+                }
+                async_std::future::pending::<()>().await;
+                Ok(())
+            }));
+        app.at("/reference/v1/seasonLeaders")
+            .with(Conditional)
+            .get(season_leaders);
+        app.at("/reference/v1/playerStats")
+            .with(Conditional)
+            .get(player_stats);
+        app.at("/chronicler/v1/players")
+            .with(Conditional)
+            .get(players);
+        app.at("/chronicler/v1/games").with(Conditional).get(games);
+        app.at("/chronicler/v2/versions")
+            .with(Conditional)
+            .get(versions);
+        app.at("/chronicler/v2/entities")
+            .with(Conditional)
+            .get(entities);
+        app.at("/webhooks/:hook").post(webhook);
+
+        let mut listener = "127.0.0.1:0".to_listener()?;
+        listener.bind(app).await?;
+        let url = listener
+            .info()
+            .first()
+            .map(|x| x.connection().to_string())
+            .ok_or_else(|| anyhow::anyhow!("Mock server isn't listening"))?;
+        async_std::task::spawn(async move { listener.accept().await });
+
+        Ok(Self {
+            url,
+            webhooks,
+            hits,
+        })
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.url, path.trim_start_matches('/'))
+    }
+
+    pub fn sources(&self) -> DataSources {
+        DataSources {
+            blaseball: self.url("blaseball"),
+            reference: self.url("reference"),
+            chronicler: self.url("chronicler"),
+        }
+    }
+
+    pub fn stream_url(&self) -> String {
+        self.sources().blaseball("events/streamData")
+    }
+
+    pub fn webhook_url(&self, hook: &str) -> String {
+        self.url(&format!("webhooks/{}", hook))
+    }
+
+    pub fn webhooks(&self) -> Vec<WebhookMessage> {
+        self.webhooks.lock().unwrap().clone()
+    }
+
+    pub fn hits(&self, path: &str) -> Vec<u16> {
+        self.hits
+            .lock()
+            .unwrap()
+            .get(path)
+            .cloned()
+            .unwrap_or_default()
+    }
+}